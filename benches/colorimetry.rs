@@ -0,0 +1,71 @@
+//! Recomputes the colorimetry of 100 captures, checking that it doesn't
+//! allocate per capture. `cargo bench --bench colorimetry`
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use sekonic_c_7000::{
+    color::{colorimetry, Observer},
+    Spectrum,
+};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// 1nm blackbody spectra from 2000K to 11900K, standing in for captures
+fn spectra() -> Vec<Spectrum> {
+    (0..100)
+        .map(|i| {
+            let kelvin = 2000. + 100. * f64::from(i);
+            let values = (380..=780)
+                .map(|nm| {
+                    let m = f64::from(nm) * 1e-9;
+                    (1e-30 / (m.powi(5) * ((1.4388e-2 / (m * kelvin)).exp() - 1.))) as f32
+                })
+                .collect();
+            Spectrum {
+                start_nm: 380,
+                step_nm: 1,
+                values,
+            }
+        })
+        .collect()
+}
+
+fn main() {
+    let spectra = spectra();
+    for observer in [Observer::TwoDegree, Observer::TenDegree] {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        for spectrum in &spectra {
+            black_box(colorimetry(black_box(spectrum), observer));
+        }
+        let elapsed = start.elapsed();
+        let allocated = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+        println!(
+            "{observer:?}: {} captures in {elapsed:?}, {:?} each, {allocated} allocations",
+            spectra.len(),
+            elapsed / spectra.len() as u32
+        );
+        assert_eq!(allocated, 0, "colorimetry allocated");
+    }
+}
@@ -0,0 +1,102 @@
+//! Scans 60 captures the way `main` does, once parsing each MR response
+//! before requesting the next and once parsing it on another thread while the
+//! next one is read, against a transport that takes `LATENCY` per request in
+//! place of the meter. `cargo bench --bench scan`
+//!
+//! Parsing an MR response takes 2-4µs here, next to two requests per capture,
+//! so at 2ms a request the two come out the same within a percent or so
+//! either way.
+
+use std::{
+    fs,
+    hint::black_box,
+    path::Path,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use sekonic_c_7000::{CaptureInfo, FieldLayout, Result, SekonicDevice, Transport};
+
+const CAPTURES: u32 = 60;
+// what a GA or MR round trip is assumed to take, no meter has been timed yet
+const LATENCY: Duration = Duration::from_millis(2);
+
+// every GA and MR request answered with the test session's, after
+// LATENCY
+struct SlowMeter {
+    ga: Vec<u8>,
+    mr: Vec<u8>,
+}
+
+impl Transport for SlowMeter {
+    fn request(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        thread::sleep(LATENCY);
+        Ok(if req.starts_with(b"GA") {
+            self.ga.clone()
+        } else {
+            self.mr.clone()
+        })
+    }
+}
+
+fn device() -> SekonicDevice<SlowMeter> {
+    let session = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/session");
+    SekonicDevice::new(SlowMeter {
+        ga: fs::read(session.join("GA0001,0001.resp")).unwrap(),
+        mr: fs::read(session.join("MR0001.resp")).unwrap(),
+    })
+}
+
+fn read(dev: &mut SekonicDevice<SlowMeter>, local_capture_id: u32) -> Vec<u8> {
+    let global_id = dev.global_capture_id(1, local_capture_id).unwrap();
+    dev.capture_info_raw(global_id).unwrap()
+}
+
+fn serial(layout: &FieldLayout) -> Vec<CaptureInfo> {
+    let mut dev = device();
+    (1..=CAPTURES)
+        .map(|id| CaptureInfo::parse_with(&read(&mut dev, id), layout).unwrap())
+        .collect()
+}
+
+// the same channel and bound as the scan in main
+fn pipelined(layout: &FieldLayout) -> Vec<CaptureInfo> {
+    let mut dev = device();
+    thread::scope(|s| {
+        let (tx, rx) = mpsc::sync_channel::<Vec<u8>>(4);
+        let parser = s.spawn(|| {
+            rx.into_iter()
+                .map(|raw| CaptureInfo::parse_with(&raw, layout).unwrap())
+                .collect()
+        });
+        for id in 1..=CAPTURES {
+            tx.send(read(&mut dev, id)).unwrap();
+        }
+        drop(tx);
+        parser.join().unwrap()
+    })
+}
+
+fn main() {
+    let layout = &FieldLayout::DEFAULT;
+    let mr = device().transport().mr.clone();
+    let start = Instant::now();
+    for _ in 0..1000 {
+        black_box(CaptureInfo::parse_with(black_box(&mr), layout).unwrap());
+    }
+    println!("parse: {:?} per MR response", start.elapsed() / 1000);
+
+    let start = Instant::now();
+    let a = black_box(serial(layout));
+    let serial_time = start.elapsed();
+    let start = Instant::now();
+    let b = black_box(pipelined(layout));
+    let pipelined_time = start.elapsed();
+    assert_eq!(a.len(), b.len());
+    println!(
+        "{CAPTURES} captures at {LATENCY:?} per request: serial {serial_time:?}, \
+         pipelined {pipelined_time:?} ({:+.1}%)",
+        100. * (pipelined_time.as_secs_f64() / serial_time.as_secs_f64() - 1.)
+    );
+}
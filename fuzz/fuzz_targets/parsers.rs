@@ -0,0 +1,18 @@
+//! The response parsers get whatever bytes the meter sends, so none of them
+//! may panic on any input, only return an error.
+//!
+//! `cargo +nightly fuzz run parsers`, from the repo root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sekonic_c_7000::{CaptureData, CaptureInfo, StorageInfoResp, TitleInfo};
+
+fuzz_target!(|data: &[u8]| {
+    // each checks its own prefix, so the same input reaches at most one of
+    // them past ParseHelper::start
+    let _ = CaptureInfo::parse(data);
+    let _ = CaptureData::parse(data);
+    let _ = StorageInfoResp::parse(data);
+    let _ = TitleInfo::parse(data);
+});
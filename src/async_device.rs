@@ -0,0 +1,316 @@
+use std::{
+    future::poll_fn,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
+};
+
+use nusb::{
+    descriptors::TransferType,
+    transfer::{Buffer, Bulk, Direction, In, Out},
+    DeviceInfo, Endpoint,
+};
+
+use crate::{
+    check_status, parse_global_capture_id, CaptureData, CaptureInfo, DeviceModel, DeviceSummary,
+    FieldLayout, FirmwareVersion, ModelInfo, SekonicError, Settings, StorageInfoResp, TitleInfo,
+    DEFAULT_TIMEOUT,
+};
+
+// same as the buffer UsbTransport reads into, a multiple of any max packet size
+const READ_LEN: usize = 8192;
+
+/// [`crate::SekonicDevice`] for async code, on top of nusb instead of libusb.
+///
+/// Works with any executor, nothing here needs a runtime. Dropping one of the
+/// request futures cancels whatever transfer it had in flight, so wrapping
+/// them in something like `tokio::time::timeout` is fine. Requests aren't
+/// retried like on the sync side.
+///
+/// Errors are `anyhow` rather than [`SekonicError`], whose `Usb` variant is
+/// for rusb. Everything that isn't a nusb error downcasts to a
+/// [`SekonicError`].
+pub struct AsyncSekonicDevice {
+    // claimed for as long as the endpoints are open, released on drop
+    _interface: nusb::Interface,
+    ep_in: Endpoint<Bulk, In>,
+    ep_out: Endpoint<Bulk, Out>,
+    timeout: Duration,
+    layout: &'static FieldLayout,
+}
+
+impl AsyncSekonicDevice {
+    /// Find the attached meter, any [`DeviceModel`], and claim its bulk interface, detaching a
+    /// kernel driver if one has it.
+    ///
+    /// Errors if there's more than one meter plugged in, like
+    /// [`crate::SekonicDevice::open`].
+    pub async fn open() -> anyhow::Result<AsyncSekonicDevice> {
+        let found: Vec<DeviceInfo> = nusb::list_devices()
+            .await?
+            .filter(|d| DeviceModel::from_ids(d.vendor_id(), d.product_id()).is_some())
+            .collect();
+        match found.as_slice() {
+            [] => Err(SekonicError::DeviceNotFound.into()),
+            [d] => Self::open_device(d).await,
+            _ => Err(SekonicError::AmbiguousDevice(found.iter().map(summary).collect()).into()),
+        }
+    }
+
+    async fn open_device(d: &DeviceInfo) -> anyhow::Result<AsyncSekonicDevice> {
+        let device = d.open().await?;
+        let config = device.active_configuration()?;
+        let found = config.interface_alt_settings().find_map(|intf| {
+            let addr = |dir| {
+                intf.endpoints()
+                    .find(|ep| ep.transfer_type() == TransferType::Bulk && ep.direction() == dir)
+                    .map(|ep| ep.address())
+            };
+            Some((
+                intf.interface_number(),
+                addr(Direction::In)?,
+                addr(Direction::Out)?,
+            ))
+        });
+        let Some((interface, in_addr, out_addr)) = found else {
+            return Err(SekonicError::NoBulkEndpoints {
+                bus: d.busnum(),
+                address: d.device_address(),
+            }
+            .into());
+        };
+
+        let interface = device.detach_and_claim_interface(interface).await?;
+        Ok(AsyncSekonicDevice {
+            ep_in: interface.endpoint(in_addr)?,
+            ep_out: interface.endpoint(out_addr)?,
+            _interface: interface,
+            timeout: DEFAULT_TIMEOUT,
+            layout: &FieldLayout::DEFAULT,
+        })
+    }
+
+    /// How long to wait for more of a response that exactly filled the last
+    /// transfer, since the meter doesn't send a zero length packet to say it's
+    /// done. Defaults to [`DEFAULT_TIMEOUT`]. Nothing else has a timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> AsyncSekonicDevice {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send `req` and return the payload of the response, like
+    /// [`crate::Transport::request`].
+    pub async fn request(&mut self, req: &[u8]) -> anyhow::Result<Vec<u8>> {
+        // anything left over from a request whose future was dropped
+        while self.ep_out.pending() > 0 {
+            self.ep_out.next_complete().await;
+        }
+        while self.ep_in.pending() > 0 {
+            self.ep_in.next_complete().await;
+        }
+
+        let mut in_flight = InFlight {
+            ep_in: &mut self.ep_in,
+            ep_out: &mut self.ep_out,
+            done: false,
+        };
+
+        in_flight.ep_out.submit(Buffer::from(req.to_vec()));
+        in_flight.ep_out.next_complete().await.status?;
+
+        in_flight.ep_in.submit(Buffer::new(READ_LEN));
+        let status = in_flight.ep_in.next_complete().await.into_result()?;
+        check_status(&status)?;
+        let ret = read_payload(in_flight.ep_in, self.timeout).await;
+        in_flight.done = true;
+        ret
+    }
+
+    // request() for payloads that get parsed, see SekonicDevice::query
+    async fn query(&mut self, req: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let resp = self.request(req).await?;
+        if resp.is_empty() {
+            return Err(
+                SekonicError::EmptyResponse(String::from_utf8_lossy(req).into_owned()).into(),
+            );
+        }
+        Ok(resp)
+    }
+
+    /// The setup sequence from [`crate::SekonicDevice::start_session`],
+    /// which also picks the MR layout from the firmware version.
+    pub async fn start_session(&mut self) -> anyhow::Result<()> {
+        for req in [&b"ST"[..], b"RT0", b"RT1", b"MN", b"SAr", b"FTr"] {
+            self.request(req).await?;
+        }
+        if let Ok(fv) = FirmwareVersion::parse(&self.request(b"FV").await?) {
+            self.layout = FieldLayout::for_firmware(&fv);
+        }
+        self.request(b"IUr").await?;
+        Ok(())
+    }
+
+    pub async fn end_session(&mut self) -> anyhow::Result<()> {
+        self.request(b"ST").await?;
+        Ok(())
+    }
+
+    pub async fn model_info(&mut self) -> anyhow::Result<ModelInfo> {
+        Ok(ModelInfo::parse(&self.query(b"MN").await?)?)
+    }
+
+    pub async fn firmware_version(&mut self) -> anyhow::Result<FirmwareVersion> {
+        Ok(FirmwareVersion::parse(&self.query(b"FV").await?)?)
+    }
+
+    pub async fn settings(&mut self) -> anyhow::Result<Settings> {
+        Ok(Settings::parse(&self.query(b"SAr").await?)?)
+    }
+
+    pub async fn storage_info(&mut self) -> anyhow::Result<StorageInfoResp> {
+        Ok(StorageInfoResp::parse(&self.query(b"MI").await?)?)
+    }
+
+    // 1 indexed
+    pub async fn title_info(&mut self, id: u32) -> anyhow::Result<TitleInfo> {
+        if id == 0 {
+            return Err(SekonicError::InvalidArgument("title ids start at 1").into());
+        }
+        Ok(TitleInfo::parse(
+            &self.query(format!("GT{id:04}").as_bytes()).await?,
+        )?)
+    }
+
+    // 1 indexed
+    pub async fn global_capture_id(
+        &mut self,
+        title_id: u32,
+        local_capture_id: u32,
+    ) -> anyhow::Result<u32> {
+        if title_id == 0 {
+            return Err(SekonicError::InvalidArgument("title ids start at 1").into());
+        }
+        if local_capture_id == 0 {
+            return Err(
+                SekonicError::InvalidArgument("capture ids within a title start at 1").into(),
+            );
+        }
+        Ok(parse_global_capture_id(
+            &self
+                .query(format!("GA{title_id:04},{local_capture_id:04}").as_bytes())
+                .await?,
+        )?)
+    }
+
+    pub async fn capture_info(&mut self, global_capture_id: u32) -> anyhow::Result<CaptureInfo> {
+        Ok(CaptureInfo::parse_with(
+            &self
+                .query(format!("MR{global_capture_id:04}").as_bytes())
+                .await?,
+            self.layout,
+        )?)
+    }
+
+    pub async fn capture_data(&mut self, global_capture_id: u32) -> anyhow::Result<CaptureData> {
+        Ok(CaptureData::parse(
+            &self
+                .query(format!("ME{global_capture_id:04}").as_bytes())
+                .await?,
+        )?)
+    }
+}
+
+fn summary(d: &DeviceInfo) -> DeviceSummary {
+    DeviceSummary {
+        bus_number: d.busnum(),
+        address: d.device_address(),
+        vendor_id: d.vendor_id(),
+        product_id: d.product_id(),
+        serial: d.serial_number().map(str::to_owned),
+    }
+}
+
+// Cancels the transfers of a request if its future is dropped part way. The
+// cancelled transfers still have to be collected, which the next request does.
+struct InFlight<'a> {
+    ep_in: &'a mut Endpoint<Bulk, In>,
+    ep_out: &'a mut Endpoint<Bulk, Out>,
+    done: bool,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.ep_in.cancel_all();
+            self.ep_out.cancel_all();
+        }
+    }
+}
+
+// Same as UsbTransport::read_payload: a transfer that fills the whole buffer
+// means there is more to come, unless nothing more shows up in time.
+async fn read_payload(
+    ep_in: &mut Endpoint<Bulk, In>,
+    timeout: Duration,
+) -> anyhow::Result<Vec<u8>> {
+    let mut ret = Vec::new();
+    loop {
+        ep_in.submit(Buffer::new(READ_LEN));
+        let completion = if ret.is_empty() {
+            ep_in.next_complete().await
+        } else {
+            let timer = Timer::start(timeout);
+            let completion = poll_fn(|cx| match ep_in.poll_next_complete(cx) {
+                Poll::Ready(c) => Poll::Ready(Some(c)),
+                Poll::Pending => timer.poll_fired(cx).map(|()| None),
+            })
+            .await;
+            match completion {
+                Some(c) => c,
+                None => {
+                    ep_in.cancel_all();
+                    ep_in.next_complete().await;
+                    break;
+                }
+            }
+        };
+        let buf = completion.into_result()?;
+        ret.extend_from_slice(&buf);
+        if buf.len() < READ_LEN {
+            break;
+        }
+    }
+    Ok(ret)
+}
+
+// Fires once after a delay, from a thread of its own so it works on any
+// executor.
+struct Timer {
+    state: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+impl Timer {
+    fn start(after: Duration) -> Timer {
+        let state = Arc::new(Mutex::new((false, None::<Waker>)));
+        let thread_state = Arc::clone(&state);
+        thread::spawn(move || {
+            thread::sleep(after);
+            let mut state = thread_state.lock().unwrap();
+            state.0 = true;
+            if let Some(waker) = state.1.take() {
+                waker.wake();
+            }
+        });
+        Timer { state }
+    }
+
+    fn poll_fired(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.0 {
+            return Poll::Ready(());
+        }
+        state.1 = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,94 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{to_hex, CaptureInfo, FieldLayout, Result, SekonicError};
+
+/// The result of scanning a meter's captures, saved so listing a meter that
+/// hasn't changed doesn't need a round trip per capture.
+///
+/// Capture infos are kept as the raw MR responses and parsed again on load,
+/// so nothing the parser doesn't know about yet is lost.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanCache {
+    // the whole MN response, since the serial number in it isn't decoded
+    device: String,
+    num_captures: u32,
+    captures: Vec<CachedCapture>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedCapture {
+    global_id: u32,
+    local_id: u32,
+    // hex
+    capture_info: String,
+}
+
+impl ScanCache {
+    /// `device` is the raw MN response, from [`crate::SekonicDevice::device_key`].
+    pub fn new(device: &[u8], num_captures: u32) -> ScanCache {
+        ScanCache {
+            device: to_hex(device),
+            num_captures,
+            captures: Vec::new(),
+        }
+    }
+
+    /// `None` if there's no cache at `path` or it can't be read.
+    pub fn load(path: &Path) -> Option<ScanCache> {
+        serde_json::from_slice(&fs::read(path).ok()?).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec(self).map_err(io::Error::from)?)?;
+        Ok(())
+    }
+
+    /// Whether this is still valid for a meter. Only the capture count is
+    /// checked, so deleting one capture and taking another on the meter goes
+    /// unnoticed.
+    pub fn matches(&self, device: &[u8], num_captures: u32) -> bool {
+        self.device == to_hex(device) && self.num_captures == num_captures
+    }
+
+    /// Add a capture, with `capture_info` being the raw MR response.
+    pub fn push(&mut self, global_id: u32, local_id: u32, capture_info: &[u8]) {
+        self.captures.push(CachedCapture {
+            global_id,
+            local_id,
+            capture_info: to_hex(capture_info),
+        });
+    }
+
+    /// `(global id, local id, capture info)` for every capture, in the order
+    /// they were added, parsed with `layout`.
+    pub fn captures(&self, layout: &FieldLayout) -> Result<Vec<(u32, u32, CaptureInfo)>> {
+        self.captures
+            .iter()
+            .map(|c| {
+                let raw = from_hex(&c.capture_info).ok_or_else(|| {
+                    SekonicError::parse(format!("cached capture {} isn't hex", c.global_id))
+                })?;
+                Ok((
+                    c.global_id,
+                    c.local_id,
+                    CaptureInfo::parse_with(&raw, layout)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,544 @@
+//! Colorimetry computed from the raw spectrum, rather than trusting the
+//! values the meter reports.
+
+mod tables;
+
+pub use tables::{CIE1931_2DEG, CIE1964_10DEG, CIE_D_S, TCS};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Spectrum, ViewingAngle};
+
+/// CIE photopic luminous efficiency V(λ), 380-780nm at 1nm. Identical to ȳ
+/// of the 1931 observer.
+pub const V_LAMBDA: [f64; 401] = {
+    let mut ret = [0.; 401];
+    let mut i = 0;
+    while i < ret.len() {
+        ret[i] = CIE1931_2DEG[i][1];
+        i += 1;
+    }
+    ret
+};
+
+// first wavelength of the tables
+pub(crate) const TABLE_START_NM: u16 = 380;
+// lm/W, so Y comes out in lux for a spectrum in W/(m²·nm)
+pub(crate) const K_M: f64 = 683.;
+// second radiation constant, m·K
+const C2: f64 = 1.4388e-2;
+
+/// Which color matching functions to integrate against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Observer {
+    /// CIE 1931 2° standard observer.
+    #[default]
+    TwoDegree,
+    /// CIE 1964 10° supplementary standard observer.
+    TenDegree,
+}
+
+impl Observer {
+    /// Color matching functions `[x̄, ȳ, z̄]`, 380-780nm at 1nm.
+    pub fn cmfs(self) -> &'static [[f64; 3]; 401] {
+        match self {
+            Observer::TwoDegree => &CIE1931_2DEG,
+            Observer::TenDegree => &CIE1964_10DEG,
+        }
+    }
+
+    fn cmf_at(self, nm: u16) -> Option<&'static [f64; 3]> {
+        self.cmfs()
+            .get(usize::from(nm.checked_sub(TABLE_START_NM)?))
+    }
+}
+
+/// The observer matching the viewing angle the meter was set to, so values
+/// computed here compare with the ones it reports.
+impl From<ViewingAngle> for Observer {
+    fn from(angle: ViewingAngle) -> Observer {
+        match angle {
+            ViewingAngle::TwoDegree => Observer::TwoDegree,
+            ViewingAngle::TenDegree => Observer::TenDegree,
+        }
+    }
+}
+
+/// Tristimulus values, CIE 1931 XYZ or CIE 1964 X₁₀Y₁₀Z₁₀ depending on the
+/// observer they were computed with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Xyz {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Xyz {
+    /// Chromaticity `(x, y)`.
+    pub fn chromaticity(&self) -> (f64, f64) {
+        let sum = self.x + self.y + self.z;
+        (self.x / sum, self.y / sum)
+    }
+
+    /// CIE 1960 UCS `(u, v)`.
+    pub fn uv_1960(&self) -> (f64, f64) {
+        let denom = self.x + 15. * self.y + 3. * self.z;
+        (4. * self.x / denom, 6. * self.y / denom)
+    }
+
+    /// Correlated color temperature in K and Duv, the same way as
+    /// [`cct_duv`] but from XYZ that's already known.
+    pub fn cct_duv(&self, observer: Observer) -> (f32, f32) {
+        let (cct, duv) = cct_duv_of(*self, observer);
+        (cct as f32, duv as f32)
+    }
+
+    /// 8-bit sRGB, for `Y` in 0-1. Out of gamut channels are clamped, so this
+    /// is only good for showing roughly what the color looks like.
+    pub fn to_srgb(&self) -> [u8; 3] {
+        let linear = [
+            3.2406 * self.x - 1.5372 * self.y - 0.4986 * self.z,
+            -0.9689 * self.x + 1.8758 * self.y + 0.0415 * self.z,
+            0.0557 * self.x - 0.2040 * self.y + 1.0570 * self.z,
+        ];
+        linear.map(|c| {
+            let c = c.clamp(0., 1.);
+            let encoded = if c <= 0.0031308 {
+                12.92 * c
+            } else {
+                1.055 * c.powf(1. / 2.4) - 0.055
+            };
+            (encoded * 255.).round() as u8
+        })
+    }
+}
+
+/// Integrate `spectrum` against `observer`. Points outside 380-780nm are
+/// ignored.
+pub fn xyz(spectrum: &Spectrum, observer: Observer) -> Xyz {
+    let mut ret = Xyz {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+    };
+    for (nm, value) in spectrum.iter_nm() {
+        let Some(cmf) = observer.cmf_at(nm) else {
+            continue;
+        };
+        let value = f64::from(value) * f64::from(spectrum.step_nm);
+        ret.x += value * cmf[0];
+        ret.y += value * cmf[1];
+        ret.z += value * cmf[2];
+    }
+    ret.x *= K_M;
+    ret.y *= K_M;
+    ret.z *= K_M;
+    ret
+}
+
+/// Correlated color temperature in K and Duv of `spectrum`.
+///
+/// Rather than McCamy's approximation, this searches along the Planckian locus
+/// (computed from the same color matching functions) for the point closest to
+/// the spectrum in CIE 1960 uv, which holds up for CCTs far from daylight.
+/// Duv is positive above the locus (greenish) and negative below it (pinkish).
+///
+/// CCT is defined with the 2° observer, [`Observer::TenDegree`] gives the
+/// equivalent against a locus computed with the 10° one.
+pub fn cct_duv(spectrum: &Spectrum, observer: Observer) -> (f32, f32) {
+    let (cct, duv) = cct_duv_of(xyz(spectrum, observer), observer);
+    (cct as f32, duv as f32)
+}
+
+fn cct_duv_of(xyz: Xyz, observer: Observer) -> (f64, f64) {
+    let uv = xyz.uv_1960();
+    let cct = cct_of(uv, observer);
+    let (u, v) = planck_uv(cct, observer);
+    (cct, (uv.0 - u).hypot(uv.1 - v).copysign(uv.1 - v))
+}
+
+fn cct_of(uv: (f64, f64), observer: Observer) -> f64 {
+    let dist = |mired: f64| {
+        let (u, v) = planck_uv(1e6 / mired, observer);
+        (uv.0 - u).hypot(uv.1 - v)
+    };
+
+    // coarse scan in 1 mired steps over 1-1000 mired (1000K-1000000K), then
+    // narrow down around the best step
+    let mut best = (1..=1000)
+        .map(f64::from)
+        .min_by(|a, b| dist(*a).total_cmp(&dist(*b)))
+        .unwrap();
+    let (mut lo, mut hi) = ((best - 1.).max(1e-3), best + 1.);
+    while hi - lo > 1e-6 {
+        let a = lo + (hi - lo) / 3.;
+        let b = hi - (hi - lo) / 3.;
+        if dist(a) < dist(b) {
+            hi = b;
+        } else {
+            lo = a;
+        }
+    }
+    best = (lo + hi) / 2.;
+    1e6 / best
+}
+
+/// Dominant wavelength in nm and excitation purity in % of the chromaticity
+/// `(x, y)`, relative to the equal energy white point. The spectral locus is
+/// that of `observer`, which should be the one `(x, y)` was computed with.
+///
+/// Chromaticities in the purple region between the ends of the spectral locus
+/// don't have a dominant wavelength, so their complementary wavelength is
+/// returned negated, as is conventional, and purity is measured against the
+/// line of purples. The white point itself has no dominant wavelength and
+/// gives NaN.
+pub fn dominant_wavelength_purity(x: f32, y: f32, observer: Observer) -> (f32, f32) {
+    const WHITE: (f64, f64) = (1. / 3., 1. / 3.);
+    let d = (f64::from(x) - WHITE.0, f64::from(y) - WHITE.1);
+    if d.0.hypot(d.1) < 1e-6 {
+        return (f32::NAN, 0.);
+    }
+
+    let cmfs = observer.cmfs();
+    let locus = |i: usize| {
+        let [x, y, z] = cmfs[i];
+        (x / (x + y + z), y / (x + y + z))
+    };
+    // first point along `dir` from the white point where it crosses the locus,
+    // as (distance along dir, wavelength)
+    let spectral_hit = |dir: (f64, f64)| {
+        (0..cmfs.len() - 1)
+            .filter_map(|i| {
+                let (t, s) = ray_segment(WHITE, dir, locus(i), locus(i + 1))?;
+                Some((t, f64::from(TABLE_START_NM) + i as f64 + s))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    };
+
+    // the sample is at distance 1 along d, so purity is 1 / (distance to the
+    // locus)
+    if let Some((t, nm)) = spectral_hit(d) {
+        return (nm as f32, (100. / t) as f32);
+    }
+    let purples = ray_segment(WHITE, d, locus(cmfs.len() - 1), locus(0));
+    let complementary = spectral_hit((-d.0, -d.1));
+    match (purples, complementary) {
+        (Some((t, _)), Some((_, nm))) => (-nm as f32, (100. / t) as f32),
+        _ => (f32::NAN, f32::NAN),
+    }
+}
+
+// Where the ray `origin + t * dir` crosses the segment from `a` to `b`, as
+// (t, fraction along the segment), if it does for t > 0.
+fn ray_segment(
+    origin: (f64, f64),
+    dir: (f64, f64),
+    a: (f64, f64),
+    b: (f64, f64),
+) -> Option<(f64, f64)> {
+    let cross = |u: (f64, f64), v: (f64, f64)| u.0 * v.1 - u.1 * v.0;
+    let seg = (b.0 - a.0, b.1 - a.1);
+    let denom = cross(dir, seg);
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+    let to_a = (a.0 - origin.0, a.1 - origin.1);
+    let t = cross(to_a, seg) / denom;
+    let s = cross(to_a, dir) / denom;
+    (t > 0. && (0. ..=1.).contains(&s)).then_some((t, s))
+}
+
+/// Everything this module computes from one spectrum.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Colorimetry {
+    pub xyz: Xyz,
+    /// Chromaticity `(x, y)`.
+    pub chromaticity: (f64, f64),
+    /// Correlated color temperature in K, see [`cct_duv`].
+    pub cct: f32,
+    pub duv: f32,
+    pub cri: CriResult,
+}
+
+/// [`xyz`], [`cct_duv`] and [`cri`] together. The spectrum is integrated
+/// against the color matching functions once and the result shared, where
+/// calling them one by one integrates it again for each. Nothing is allocated,
+/// so this is the one to use when recomputing a whole batch of captures.
+pub fn colorimetry(spectrum: &Spectrum, observer: Observer) -> Colorimetry {
+    let xyz = xyz(spectrum, observer);
+    let (cct, duv) = cct_duv_of(xyz, observer);
+    Colorimetry {
+        xyz,
+        chromaticity: xyz.chromaticity(),
+        cct: cct as f32,
+        duv: duv as f32,
+        cri: cri_at(spectrum, cct, observer),
+    }
+}
+
+/// CIE 13.3 color rendering indices.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CriResult {
+    /// General color rendering index, the mean of R1-R8.
+    pub ra: f32,
+    /// Special color rendering indices R1-R14.
+    pub r: [f32; 14],
+}
+
+/// CIE 13.3 color rendering index of `spectrum`, computed independently of what
+/// the meter reports.
+///
+/// The reference illuminant is picked from the CCT of `spectrum`: a Planckian
+/// radiator below 5000K, and CIE daylight of the same CCT from 5000K up. CIE
+/// daylight is only defined from 4000K to 25000K, above that it's
+/// extrapolated.
+///
+/// The 5nm test color sample and daylight tables are linearly interpolated
+/// onto the wavelength axis of `spectrum`, so narrow lines in a 1nm spectrum
+/// are weighted correctly. Only 380-780nm is used.
+///
+/// CIE 13.3 only considers the result meaningful for sources close to the
+/// Planckian locus (|Duv| < 5.4e-3), and specifies the 2° observer. Other
+/// observers are for comparing with a meter set to them.
+pub fn cri(spectrum: &Spectrum, observer: Observer) -> CriResult {
+    let cct = cct_of(xyz(spectrum, observer).uv_1960(), observer);
+    cri_at(spectrum, cct, observer)
+}
+
+// `cri` with the CCT of `spectrum` already known
+fn cri_at(spectrum: &Spectrum, cct: f64, observer: Observer) -> CriResult {
+    // the reference illuminant is evaluated on the wavelengths of `spectrum`
+    let daylight = (cct >= 5000.).then(|| daylight(cct));
+    let reference = |nm: u16| match &daylight {
+        Some(daylight) => interpolate_5nm(daylight, nm),
+        None => planck(f64::from(nm), cct),
+    };
+    let test = |reflectance| sampled_xyz(spectrum, |_, value| value, reflectance, observer);
+    let reference =
+        |reflectance| sampled_xyz(spectrum, |nm, _| reference(nm), reflectance, observer);
+
+    let white_k = test(None);
+    let white_r = reference(None);
+    let (uk, vk) = white_k.uv_1960();
+    let (ur, vr) = white_r.uv_1960();
+    let (ck, dk) = cd(uk, vk);
+    let (cr, dr) = cd(ur, vr);
+
+    let r = std::array::from_fn(|i| {
+        let sample_k = test(Some(&TCS[i]));
+        let sample_r = reference(Some(&TCS[i]));
+
+        // von Kries adaptation of the sample under the test source to the
+        // reference white
+        let (u, v) = sample_k.uv_1960();
+        let (c, d) = cd(u, v);
+        let (c, d) = (cr / ck * c, dr / dk * d);
+        let denom = 16.518 + 1.481 * c - d;
+        let adapted = (
+            (10.872 + 0.404 * c - 4. * d) / denom,
+            5.520 / denom,
+            100. * sample_k.y / white_k.y,
+        );
+        let (u, v) = sample_r.uv_1960();
+        let reference = (u, v, 100. * sample_r.y / white_r.y);
+
+        let a = uvw_1964(adapted, (ur, vr));
+        let b = uvw_1964(reference, (ur, vr));
+        let delta_e = ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt();
+        (100. - 4.6 * delta_e) as f32
+    });
+    CriResult {
+        ra: r[..8].iter().sum::<f32>() / 8.,
+        r,
+    }
+}
+
+// XYZ of a source, optionally reflected off a 5nm `reflectance`, on the
+// wavelengths of `spectrum`. `source` gets each wavelength and the value of
+// `spectrum` there. Only ever used as ratios so no need for K_M
+fn sampled_xyz(
+    spectrum: &Spectrum,
+    source: impl Fn(u16, f64) -> f64,
+    reflectance: Option<&[f64; 81]>,
+    observer: Observer,
+) -> Xyz {
+    let mut ret = Xyz {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+    };
+    for (nm, value) in spectrum.iter_nm() {
+        let Some(cmf) = observer.cmf_at(nm) else {
+            continue;
+        };
+        let s = source(nm, f64::from(value)) * reflectance.map_or(1., |r| interpolate_5nm(r, nm));
+        ret.x += s * cmf[0];
+        ret.y += s * cmf[1];
+        ret.z += s * cmf[2];
+    }
+    ret
+}
+
+// linear interpolation into a 380-780nm table at 5nm steps
+fn interpolate_5nm(table: &[f64; 81], nm: u16) -> f64 {
+    let offset = nm.saturating_sub(TABLE_START_NM).min(400);
+    let (i, frac) = (usize::from(offset / 5), f64::from(offset % 5) / 5.);
+    match table.get(i + 1) {
+        Some(next) => table[i] * (1. - frac) + next * frac,
+        None => table[i],
+    }
+}
+
+fn cd(u: f64, v: f64) -> (f64, f64) {
+    ((4. - u - 10. * v) / v, (1.708 * v + 0.404 - 1.481 * u) / v)
+}
+
+// CIE 1964 U*V*W* of `(u, v, Y)` relative to the white point `(u0, v0)`
+fn uvw_1964((u, v, y): (f64, f64, f64), (u0, v0): (f64, f64)) -> (f64, f64, f64) {
+    let w = 25. * y.cbrt() - 17.;
+    (13. * w * (u - u0), 13. * w * (v - v0), w)
+}
+
+// CIE daylight at `kelvin`, 380-780nm at 5nm
+fn daylight(kelvin: f64) -> [f64; 81] {
+    let t = kelvin;
+    let xd = if t <= 7000. {
+        0.244063 + 0.09911e3 / t + 2.9678e6 / t.powi(2) - 4.6070e9 / t.powi(3)
+    } else {
+        0.237040 + 0.24748e3 / t + 1.9018e6 / t.powi(2) - 2.0064e9 / t.powi(3)
+    };
+    let yd = -3. * xd * xd + 2.87 * xd - 0.275;
+    let m = 0.0241 + 0.2562 * xd - 0.7341 * yd;
+    let m1 = (-1.3515 - 1.7703 * xd + 5.9114 * yd) / m;
+    let m2 = (0.0300 - 31.4424 * xd + 30.0717 * yd) / m;
+    std::array::from_fn(|i| CIE_D_S[0][i] + m1 * CIE_D_S[1][i] + m2 * CIE_D_S[2][i])
+}
+
+/// CIE 1960 uv of a blackbody at `kelvin`.
+fn planck_uv(kelvin: f64, observer: Observer) -> (f64, f64) {
+    let mut xyz = Xyz {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+    };
+    for (i, cmf) in observer.cmfs().iter().enumerate() {
+        let radiance = planck(f64::from(TABLE_START_NM) + i as f64, kelvin);
+        xyz.x += radiance * cmf[0];
+        xyz.y += radiance * cmf[1];
+        xyz.z += radiance * cmf[2];
+    }
+    xyz.uv_1960()
+}
+
+// relative spectral radiance of a blackbody. The first radiation constant only
+// scales, so it's left out
+fn planck(nm: f64, kelvin: f64) -> f64 {
+    let m = nm * 1e-9;
+    1. / (m.powi(5) * ((C2 / (m * kelvin)).exp() - 1.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spectrum_1nm(f: impl Fn(u16) -> f64) -> Spectrum {
+        Spectrum {
+            start_nm: TABLE_START_NM,
+            step_nm: 1,
+            values: (380..=780).map(|nm| f(nm) as f32).collect(),
+        }
+    }
+
+    fn d65() -> Spectrum {
+        let table = daylight(6504.);
+        spectrum_1nm(|nm| interpolate_5nm(&table, nm))
+    }
+
+    fn assert_close(what: &str, got: f64, want: f64, tolerance: f64) {
+        assert!(
+            (got - want).abs() <= tolerance,
+            "{what} is {got}, expected {want} ± {tolerance}"
+        );
+    }
+
+    #[test]
+    fn illuminant_a() {
+        let a = spectrum_1nm(|nm| planck(f64::from(nm), 2856.));
+        let (x, y) = xyz(&a, Observer::TwoDegree).chromaticity();
+        assert_close("x", x, 0.44757, 2e-4);
+        assert_close("y", y, 0.40745, 2e-4);
+        let (cct, duv) = cct_duv(&a, Observer::TwoDegree);
+        assert_close("CCT", f64::from(cct), 2856., 1.);
+        assert_close("Duv", f64::from(duv), 0., 1e-5);
+    }
+
+    #[test]
+    fn illuminant_d65() {
+        let (x, y) = xyz(&d65(), Observer::TwoDegree).chromaticity();
+        assert_close("x", x, 0.31272, 2e-4);
+        assert_close("y", y, 0.32903, 2e-4);
+        let (cct, duv) = cct_duv(&d65(), Observer::TwoDegree);
+        assert_close("CCT", f64::from(cct), 6504., 5.);
+        assert_close("Duv", f64::from(duv), 0.0032, 2e-4);
+    }
+
+    #[test]
+    fn blackbodies_outside_the_coarse_scan_steps() {
+        for kelvin in [1200., 2000., 4500., 20000., 100000.] {
+            let source = spectrum_1nm(|nm| planck(f64::from(nm), kelvin));
+            let (cct, _) = cct_duv(&source, Observer::TwoDegree);
+            assert_close("CCT", f64::from(cct), kelvin, kelvin * 1e-4);
+        }
+    }
+
+    #[test]
+    fn cri_of_the_reference_is_100() {
+        let d65 = cri(&d65(), Observer::TwoDegree);
+        assert_close("Ra", f64::from(d65.ra), 100., 0.1);
+        for r in d65.r {
+            assert_close("Ri", f64::from(r), 100., 0.1);
+        }
+        // below 5000K the reference is a blackbody
+        let a = spectrum_1nm(|nm| planck(f64::from(nm), 2856.));
+        assert_close("Ra", f64::from(cri(&a, Observer::TwoDegree).ra), 100., 0.1);
+    }
+
+    #[test]
+    fn lux_and_ppfd() {
+        // 1 W/m² at 555nm, where V(λ) peaks, is 683 lm/m²
+        let line = spectrum_1nm(|nm| if nm == 555 { 1. } else { 0. });
+        assert_close("lux", f64::from(line.lux()), 683., 0.1);
+
+        // 1 W/(m²·nm) across 400-700nm, each nm worth nm / 119.627 µmol
+        let flat = spectrum_1nm(|_| 1.);
+        let photons = (400..=700).map(f64::from).sum::<f64>() / 119.627;
+        assert_close("PPFD", f64::from(flat.ppfd()), photons, 1e-3 * photons);
+    }
+
+    #[test]
+    fn dominant_wavelength_of_a_narrow_band() {
+        let band = spectrum_1nm(|nm| (-(f64::from(nm) - 520.).powi(2) / 2.).exp());
+        let (x, y) = xyz(&band, Observer::TwoDegree).chromaticity();
+        let (nm, purity) = dominant_wavelength_purity(x as f32, y as f32, Observer::TwoDegree);
+        assert_close("dominant wavelength", f64::from(nm), 520., 0.5);
+        assert_close("purity", f64::from(purity), 100., 1.);
+
+        // halfway to the white point is half as pure
+        let (wx, wy) = ((x + 1. / 3.) / 2., (y + 1. / 3.) / 2.);
+        let (nm, purity) = dominant_wavelength_purity(wx as f32, wy as f32, Observer::TwoDegree);
+        assert_close("dominant wavelength", f64::from(nm), 520., 0.5);
+        assert_close("purity", f64::from(purity), 50., 1.);
+    }
+
+    #[test]
+    fn purples_are_complementary() {
+        let purple = spectrum_1nm(|nm| if nm == 450 || nm == 650 { 1. } else { 0. });
+        let (x, y) = xyz(&purple, Observer::TwoDegree).chromaticity();
+        let (nm, purity) = dominant_wavelength_purity(x as f32, y as f32, Observer::TwoDegree);
+        assert!((-570. ..-490.).contains(&nm), "{nm}");
+        assert!(purity > 0. && purity <= 100.5, "{purity}");
+
+        let (nm, _) = dominant_wavelength_purity(1. / 3., 1. / 3., Observer::TwoDegree);
+        assert!(nm.is_nan());
+    }
+}
@@ -0,0 +1,973 @@
+// Tabulated CIE data, 1nm steps from 380nm to 780nm unless noted otherwise.
+
+// measured data, not approximations of constants
+#![allow(clippy::approx_constant)]
+
+/// CIE 1931 2° standard observer color matching functions, `[x̄, ȳ, z̄]`.
+pub const CIE1931_2DEG: [[f64; 3]; 401] = [
+    [0.001368, 0.000039, 0.006450001],
+    [0.00150205, 0.0000428264, 0.007083216],
+    [0.001642328, 0.0000469146, 0.007745488],
+    [0.001802382, 0.0000515896, 0.008501152],
+    [0.001995757, 0.0000571764, 0.009414544],
+    [0.002236, 0.000064, 0.01054999],
+    [0.002535385, 0.0000723442, 0.0119658],
+    [0.002892603, 0.0000822122, 0.01365587],
+    [0.003300829, 0.0000935082, 0.01558805],
+    [0.003753236, 0.0001061361, 0.01773015],
+    [0.004243, 0.00012, 0.02005001],
+    [0.004762389, 0.000134984, 0.02251136],
+    [0.005330048, 0.000151492, 0.02520288],
+    [0.005978712, 0.000170208, 0.02827972],
+    [0.006741117, 0.000191816, 0.03189704],
+    [0.00765, 0.000217, 0.03621],
+    [0.008751373, 0.0002469067, 0.04143771],
+    [0.01002888, 0.00028124, 0.04750372],
+    [0.0114217, 0.00031852, 0.05411988],
+    [0.01286901, 0.0003572667, 0.06099803],
+    [0.01431, 0.000396, 0.06785001],
+    [0.01570443, 0.0004337147, 0.07448632],
+    [0.01714744, 0.000473024, 0.08136156],
+    [0.01878122, 0.000517876, 0.08915364],
+    [0.02074801, 0.0005722187, 0.09854048],
+    [0.02319, 0.00064, 0.1102],
+    [0.02620736, 0.00072456, 0.1246133],
+    [0.02978248, 0.0008255, 0.1417017],
+    [0.03388092, 0.00094116, 0.1613035],
+    [0.03846824, 0.00106988, 0.1832568],
+    [0.04351, 0.00121, 0.2074],
+    [0.0489956, 0.001362091, 0.2336921],
+    [0.0550226, 0.001530752, 0.2626114],
+    [0.0617188, 0.001720368, 0.2947746],
+    [0.069212, 0.001935323, 0.3307985],
+    [0.07763, 0.00218, 0.3713],
+    [0.08695811, 0.0024548, 0.4162091],
+    [0.09717672, 0.002764, 0.4654642],
+    [0.1084063, 0.0031178, 0.5196948],
+    [0.1207672, 0.0035264, 0.5795303],
+    [0.13438, 0.004, 0.6456],
+    [0.1493582, 0.00454624, 0.7184838],
+    [0.1653957, 0.00515932, 0.7967133],
+    [0.1819831, 0.00582928, 0.8778459],
+    [0.198611, 0.00654616, 0.959439],
+    [0.21477, 0.0073, 1.0390501],
+    [0.2301868, 0.008086507, 1.1153673],
+    [0.2448797, 0.00890872, 1.1884971],
+    [0.2587773, 0.00976768, 1.2581233],
+    [0.2718079, 0.01066443, 1.3239296],
+    [0.2839, 0.0116, 1.3856],
+    [0.2949438, 0.01257317, 1.4426352],
+    [0.3048965, 0.01358272, 1.4948035],
+    [0.3137873, 0.01462968, 1.5421903],
+    [0.3216454, 0.01571509, 1.5848807],
+    [0.3285, 0.01684, 1.62296],
+    [0.3343513, 0.01800736, 1.6564048],
+    [0.3392101, 0.01921448, 1.6852959],
+    [0.3431213, 0.02045392, 1.7098745],
+    [0.3461296, 0.02171824, 1.7303821],
+    [0.34828, 0.023, 1.74706],
+    [0.3495999, 0.02429461, 1.7600446],
+    [0.3501474, 0.02561024, 1.7696233],
+    [0.350013, 0.02695857, 1.7762637],
+    [0.349287, 0.02835125, 1.7804334],
+    [0.34806, 0.0298, 1.7826],
+    [0.3463733, 0.03131083, 1.7829682],
+    [0.3442624, 0.03288368, 1.7816998],
+    [0.3418088, 0.03452112, 1.7791982],
+    [0.3390941, 0.03622571, 1.7758671],
+    [0.3362, 0.038, 1.77211],
+    [0.3331977, 0.03984667, 1.7682589],
+    [0.3300411, 0.041768, 1.764039],
+    [0.3266357, 0.043766, 1.7589438],
+    [0.3228868, 0.04584267, 1.7524663],
+    [0.3187, 0.048, 1.7441],
+    [0.3140251, 0.05024368, 1.7335595],
+    [0.308884, 0.05257304, 1.7208581],
+    [0.3032904, 0.05498056, 1.7059369],
+    [0.2972579, 0.05745872, 1.6887372],
+    [0.2908, 0.06, 1.6692],
+    [0.2839701, 0.06260197, 1.6475287],
+    [0.2767214, 0.06527752, 1.6234127],
+    [0.2689178, 0.06804208, 1.5960223],
+    [0.2604227, 0.07091109, 1.564528],
+    [0.2511, 0.0739, 1.5281],
+    [0.2408475, 0.077016, 1.4861114],
+    [0.2298512, 0.0802664, 1.4395215],
+    [0.2184072, 0.0836668, 1.3898799],
+    [0.2068115, 0.0872328, 1.3387362],
+    [0.19536, 0.09098, 1.28764],
+    [0.1842136, 0.09491755, 1.2374223],
+    [0.1733273, 0.09904584, 1.1878243],
+    [0.1626881, 0.1033674, 1.1387611],
+    [0.1522833, 0.1078846, 1.090148],
+    [0.1421, 0.1126, 1.0419],
+    [0.1321786, 0.117532, 0.9941976],
+    [0.1225696, 0.1226744, 0.9473473],
+    [0.1132752, 0.1279928, 0.9014531],
+    [0.1042979, 0.1334528, 0.8566193],
+    [0.09564, 0.13902, 0.8129501],
+    [0.08729955, 0.1446764, 0.7705173],
+    [0.07930804, 0.1504693, 0.7294448],
+    [0.07171776, 0.1564619, 0.6899136],
+    [0.06458099, 0.1627177, 0.6521049],
+    [0.05795001, 0.1693, 0.6162],
+    [0.05186211, 0.1762431, 0.5823286],
+    [0.04628152, 0.1835581, 0.5504162],
+    [0.04115088, 0.1912735, 0.5203376],
+    [0.03641283, 0.199418, 0.4919673],
+    [0.03201, 0.20802, 0.46518],
+    [0.0279172, 0.2171199, 0.4399246],
+    [0.0241444, 0.2267345, 0.4161836],
+    [0.020687, 0.2368571, 0.3938822],
+    [0.0175404, 0.2474812, 0.3729459],
+    [0.0147, 0.2586, 0.3533],
+    [0.01216179, 0.2701849, 0.3348578],
+    [0.00991996, 0.2822939, 0.3175521],
+    [0.00796724, 0.2950505, 0.3013375],
+    [0.006296346, 0.308578, 0.2861686],
+    [0.0049, 0.323, 0.272],
+    [0.003777173, 0.3384021, 0.2588171],
+    [0.00294532, 0.3546858, 0.2464838],
+    [0.00242488, 0.3716986, 0.2347718],
+    [0.002236293, 0.3892875, 0.2234533],
+    [0.0024, 0.4073, 0.2123],
+    [0.00292552, 0.4256299, 0.2011692],
+    [0.00383656, 0.4443096, 0.1901196],
+    [0.00517484, 0.4633944, 0.1792254],
+    [0.00698208, 0.4829395, 0.1685608],
+    [0.0093, 0.503, 0.1582],
+    [0.01214949, 0.5235693, 0.1481383],
+    [0.01553588, 0.544512, 0.1383758],
+    [0.01947752, 0.56569, 0.1289942],
+    [0.02399277, 0.5869653, 0.1200751],
+    [0.0291, 0.6082, 0.1117],
+    [0.03481485, 0.6293456, 0.1039048],
+    [0.04112016, 0.6503068, 0.09666748],
+    [0.04798504, 0.6708752, 0.08998272],
+    [0.05537861, 0.6908424, 0.08384531],
+    [0.06327, 0.71, 0.07824999],
+    [0.07163501, 0.7281852, 0.07320899],
+    [0.08046224, 0.7454636, 0.06867816],
+    [0.08973996, 0.7619694, 0.06456784],
+    [0.09945645, 0.7778368, 0.06078835],
+    [0.1096, 0.7932, 0.05725001],
+    [0.1201674, 0.8081104, 0.05390435],
+    [0.1311145, 0.8224962, 0.05074664],
+    [0.1423679, 0.8363068, 0.04775276],
+    [0.1538542, 0.8494916, 0.04489859],
+    [0.1655, 0.862, 0.04216],
+    [0.1772571, 0.8738108, 0.03950728],
+    [0.18914, 0.8849624, 0.03693564],
+    [0.2011694, 0.8954936, 0.03445836],
+    [0.2133658, 0.9054432, 0.03208872],
+    [0.2257499, 0.9148501, 0.02984],
+    [0.2383209, 0.9237348, 0.02771181],
+    [0.2510668, 0.9320924, 0.02569444],
+    [0.2639922, 0.9399226, 0.02378716],
+    [0.2771017, 0.9472252, 0.02198925],
+    [0.2904, 0.954, 0.0203],
+    [0.3038912, 0.9602561, 0.01871805],
+    [0.3175726, 0.9660074, 0.01724036],
+    [0.3314384, 0.9712606, 0.01586364],
+    [0.3454828, 0.9760225, 0.01458461],
+    [0.3597, 0.9803, 0.0134],
+    [0.3740839, 0.9840924, 0.01230723],
+    [0.3886396, 0.9874182, 0.01130188],
+    [0.4033784, 0.9903128, 0.01037792],
+    [0.4183115, 0.9928116, 0.009529306],
+    [0.4334499, 0.9949501, 0.008749999],
+    [0.4487953, 0.9967108, 0.0080352],
+    [0.464336, 0.9980983, 0.0073816],
+    [0.480064, 0.999112, 0.0067854],
+    [0.4959713, 0.9997482, 0.0062428],
+    [0.5120501, 1.0, 0.005749999],
+    [0.5282959, 0.9998567, 0.0053036],
+    [0.5446916, 0.9993046, 0.0048998],
+    [0.5612094, 0.9983255, 0.0045342],
+    [0.5778215, 0.9968987, 0.0042024],
+    [0.5945, 0.995, 0.0039],
+    [0.6112209, 0.9926005, 0.0036232],
+    [0.6279758, 0.9897426, 0.0033706],
+    [0.6447602, 0.9864444, 0.0031414],
+    [0.6615697, 0.9827241, 0.0029348],
+    [0.6784, 0.9786, 0.002749999],
+    [0.6952392, 0.9740837, 0.0025852],
+    [0.7120586, 0.9691712, 0.0024386],
+    [0.7288284, 0.9638568, 0.0023094],
+    [0.7455188, 0.9581349, 0.0021968],
+    [0.7621, 0.952, 0.0021],
+    [0.7785432, 0.9454504, 0.002017733],
+    [0.7948256, 0.9384992, 0.0019482],
+    [0.8109264, 0.9311628, 0.0018898],
+    [0.8268248, 0.9234576, 0.001840933],
+    [0.8425, 0.9154, 0.0018],
+    [0.8579325, 0.9070064, 0.001766267],
+    [0.8730816, 0.8982772, 0.0017378],
+    [0.8878944, 0.8892048, 0.0017112],
+    [0.9023181, 0.8797816, 0.001683067],
+    [0.9163, 0.87, 0.001650001],
+    [0.9297995, 0.8598613, 0.001610133],
+    [0.9427984, 0.849392, 0.0015644],
+    [0.9552776, 0.838622, 0.0015136],
+    [0.9672179, 0.8275813, 0.001458533],
+    [0.9786, 0.8163, 0.0014],
+    [0.9893856, 0.8047947, 0.001336667],
+    [0.9995488, 0.793082, 0.00127],
+    [1.0090892, 0.781192, 0.001205],
+    [1.0180064, 0.7691547, 0.001146667],
+    [1.0263, 0.757, 0.0011],
+    [1.0339827, 0.7447541, 0.0010688],
+    [1.040986, 0.7324224, 0.0010494],
+    [1.047188, 0.7200036, 0.0010356],
+    [1.0524667, 0.7074965, 0.0010212],
+    [1.0567, 0.6949, 0.001],
+    [1.0597944, 0.6822192, 0.00096864],
+    [1.0617992, 0.6694716, 0.00092992],
+    [1.0628068, 0.6566744, 0.00088688],
+    [1.0629096, 0.6438448, 0.00084256],
+    [1.0622, 0.631, 0.0008],
+    [1.0607352, 0.6181555, 0.00076096],
+    [1.0584436, 0.6053144, 0.00072368],
+    [1.0552244, 0.5924756, 0.00068592],
+    [1.0509768, 0.5796379, 0.00064544],
+    [1.0456, 0.5668, 0.0006],
+    [1.0390369, 0.5539611, 0.0005478667],
+    [1.0313608, 0.5411372, 0.0004916],
+    [1.0226662, 0.5283528, 0.0004354],
+    [1.0130477, 0.5156323, 0.0003834667],
+    [1.0026, 0.503, 0.00034],
+    [0.9913675, 0.4904688, 0.0003072533],
+    [0.9793314, 0.4780304, 0.00028316],
+    [0.9664916, 0.4656776, 0.00026544],
+    [0.9528479, 0.4534032, 0.0002518133],
+    [0.9384, 0.4412, 0.00024],
+    [0.923194, 0.42908, 0.0002295467],
+    [0.907244, 0.417036, 0.00022064],
+    [0.890502, 0.405032, 0.00021196],
+    [0.87292, 0.393032, 0.0002021867],
+    [0.8544499, 0.381, 0.00019],
+    [0.835084, 0.3689184, 0.0001742133],
+    [0.814946, 0.3568272, 0.00015564],
+    [0.794186, 0.3447768, 0.00013596],
+    [0.772954, 0.3328176, 0.0001168533],
+    [0.7514, 0.321, 0.0001],
+    [0.7295836, 0.3093381, 0.0000861333],
+    [0.7075888, 0.2978504, 0.0000746],
+    [0.6856022, 0.2865936, 0.000065],
+    [0.6638104, 0.2756245, 0.0000569333],
+    [0.6424, 0.265, 0.00005],
+    [0.6215149, 0.2547632, 0.00004416],
+    [0.6011138, 0.2448896, 0.00003948],
+    [0.5811052, 0.2353344, 0.00003572],
+    [0.5613977, 0.2260528, 0.00003264],
+    [0.5419, 0.217, 0.00003],
+    [0.5225995, 0.2081616, 0.0000276533],
+    [0.5035464, 0.1995488, 0.00002556],
+    [0.4847436, 0.1911552, 0.00002364],
+    [0.4661939, 0.1829744, 0.0000218133],
+    [0.4479, 0.175, 0.00002],
+    [0.4298613, 0.1672235, 0.0000181333],
+    [0.412098, 0.1596464, 0.0000162],
+    [0.394644, 0.1522776, 0.0000142],
+    [0.3775333, 0.1451259, 0.0000121333],
+    [0.3608, 0.1382, 0.00001],
+    [0.3444563, 0.1315003, 0.0000077333],
+    [0.3285168, 0.1250248, 0.0000054],
+    [0.3130192, 0.1187792, 0.0000032],
+    [0.2980011, 0.1127691, 0.0000013333],
+    [0.2835, 0.107, 0.0],
+    [0.2695448, 0.1014762, 0.0],
+    [0.2561184, 0.09618864, 0.0],
+    [0.2431896, 0.09112296, 0.0],
+    [0.2307272, 0.08626485, 0.0],
+    [0.2187, 0.0816, 0.0],
+    [0.2070971, 0.07712064, 0.0],
+    [0.1959232, 0.07282552, 0.0],
+    [0.1851708, 0.06871008, 0.0],
+    [0.1748323, 0.06476976, 0.0],
+    [0.1649, 0.061, 0.0],
+    [0.1553667, 0.05739621, 0.0],
+    [0.14623, 0.05395504, 0.0],
+    [0.13749, 0.05067376, 0.0],
+    [0.1291467, 0.04754965, 0.0],
+    [0.1212, 0.04458, 0.0],
+    [0.1136397, 0.04175872, 0.0],
+    [0.106465, 0.03908496, 0.0],
+    [0.09969044, 0.03656384, 0.0],
+    [0.09333061, 0.03420048, 0.0],
+    [0.0874, 0.032, 0.0],
+    [0.08190096, 0.02996261, 0.0],
+    [0.07680428, 0.02807664, 0.0],
+    [0.07207712, 0.02632936, 0.0],
+    [0.06768664, 0.02470805, 0.0],
+    [0.0636, 0.0232, 0.0],
+    [0.05980685, 0.02180077, 0.0],
+    [0.05628216, 0.02050112, 0.0],
+    [0.05297104, 0.01928108, 0.0],
+    [0.04981861, 0.01812069, 0.0],
+    [0.04677, 0.017, 0.0],
+    [0.04378405, 0.01590379, 0.0],
+    [0.04087536, 0.01483718, 0.0],
+    [0.03807264, 0.01381068, 0.0],
+    [0.03540461, 0.01283478, 0.0],
+    [0.0329, 0.01192, 0.0],
+    [0.03056419, 0.01106831, 0.0],
+    [0.02838056, 0.01027339, 0.0],
+    [0.02634484, 0.009533311, 0.0],
+    [0.02445275, 0.008846157, 0.0],
+    [0.0227, 0.00821, 0.0],
+    [0.02108429, 0.007623781, 0.0],
+    [0.01959988, 0.007085424, 0.0],
+    [0.01823732, 0.006591476, 0.0],
+    [0.01698717, 0.006138485, 0.0],
+    [0.01584, 0.005723, 0.0],
+    [0.01479064, 0.005343059, 0.0],
+    [0.01383132, 0.004995796, 0.0],
+    [0.01294868, 0.004676404, 0.0],
+    [0.0121292, 0.004380075, 0.0],
+    [0.01135916, 0.004102, 0.0],
+    [0.01062935, 0.003838453, 0.0],
+    [0.009938846, 0.003589099, 0.0],
+    [0.009288422, 0.003354219, 0.0],
+    [0.008678854, 0.003134093, 0.0],
+    [0.008110916, 0.002929, 0.0],
+    [0.007582388, 0.002738139, 0.0],
+    [0.007088746, 0.002559876, 0.0],
+    [0.006627313, 0.002393244, 0.0],
+    [0.006195408, 0.002237275, 0.0],
+    [0.005790346, 0.002091, 0.0],
+    [0.005409826, 0.001953587, 0.0],
+    [0.005052583, 0.00182458, 0.0],
+    [0.004717512, 0.00170358, 0.0],
+    [0.004403507, 0.001590187, 0.0],
+    [0.004109457, 0.001484, 0.0],
+    [0.003833913, 0.001384496, 0.0],
+    [0.003575748, 0.001291268, 0.0],
+    [0.003334342, 0.001204092, 0.0],
+    [0.003109075, 0.001122744, 0.0],
+    [0.002899327, 0.001047, 0.0],
+    [0.002704348, 0.0009765896, 0.0],
+    [0.00252302, 0.0009111088, 0.0],
+    [0.002354168, 0.0008501332, 0.0],
+    [0.002196616, 0.0007932384, 0.0],
+    [0.00204919, 0.00074, 0.0],
+    [0.00191096, 0.0006900827, 0.0],
+    [0.001781438, 0.00064331, 0.0],
+    [0.00166011, 0.000599496, 0.0],
+    [0.001546459, 0.0005584547, 0.0],
+    [0.001439971, 0.00052, 0.0],
+    [0.001340042, 0.0004839136, 0.0],
+    [0.001246275, 0.0004500528, 0.0],
+    [0.001158471, 0.0004183452, 0.0],
+    [0.00107643, 0.0003887184, 0.0],
+    [0.0009999493, 0.0003611, 0.0],
+    [0.0009287358, 0.0003353835, 0.0],
+    [0.0008624332, 0.0003114404, 0.0],
+    [0.0008007503, 0.0002891656, 0.0],
+    [0.000743396, 0.0002684539, 0.0],
+    [0.0006900786, 0.0002492, 0.0],
+    [0.0006405156, 0.0002313019, 0.0],
+    [0.0005945021, 0.0002146856, 0.0],
+    [0.0005518646, 0.0001992884, 0.0],
+    [0.000512429, 0.0001850475, 0.0],
+    [0.0004760213, 0.0001719, 0.0],
+    [0.0004424536, 0.0001597781, 0.0],
+    [0.0004115117, 0.0001486044, 0.0],
+    [0.0003829814, 0.0001383016, 0.0],
+    [0.0003566491, 0.0001287925, 0.0],
+    [0.0003323011, 0.00012, 0.0],
+    [0.0003097586, 0.0001118595, 0.0],
+    [0.0002888871, 0.0001043224, 0.0],
+    [0.0002695394, 0.0000973356, 0.0],
+    [0.0002515682, 0.0000908459, 0.0],
+    [0.0002348261, 0.0000848, 0.0],
+    [0.000219171, 0.0000791467, 0.0],
+    [0.0002045258, 0.000073858, 0.0],
+    [0.0001908405, 0.000068916, 0.0],
+    [0.0001780654, 0.0000643027, 0.0],
+    [0.0001661505, 0.00006, 0.0],
+    [0.0001550236, 0.0000559819, 0.0],
+    [0.0001446219, 0.0000522256, 0.0],
+    [0.0001349098, 0.0000487184, 0.0],
+    [0.000125852, 0.0000454475, 0.0],
+    [0.000117413, 0.0000424, 0.0],
+    [0.0001095515, 0.000039561, 0.0],
+    [0.0001022245, 0.0000369151, 0.0],
+    [0.0000953945, 0.0000344487, 0.0],
+    [0.0000890239, 0.0000321482, 0.0],
+    [0.0000830753, 0.00003, 0.0],
+    [0.0000775127, 0.0000279913, 0.0],
+    [0.000072313, 0.0000261136, 0.0],
+    [0.0000674578, 0.0000243602, 0.0],
+    [0.0000629284, 0.0000227246, 0.0],
+    [0.0000587065, 0.0000212, 0.0],
+    [0.0000547703, 0.0000197786, 0.0],
+    [0.0000510992, 0.0000184529, 0.0],
+    [0.0000476765, 0.0000172169, 0.0],
+    [0.0000444857, 0.0000160646, 0.0],
+    [0.0000415099, 0.00001499, 0.0],
+];
+
+/// CIE 1964 10° standard observer color matching functions, `[x̄₁₀, ȳ₁₀, z̄₁₀]`.
+pub const CIE1964_10DEG: [[f64; 3]; 401] = [
+    [0.000159952, 0.000017364, 0.000704776],
+    [0.00021508, 0.000023327, 0.00094823],
+    [0.00028749, 0.00003115, 0.0012682],
+    [0.00038199, 0.00004135, 0.0016861],
+    [0.00050455, 0.00005456, 0.0022285],
+    [0.00066244, 0.00007156, 0.0029278],
+    [0.0008645, 0.0000933, 0.0038237],
+    [0.0011215, 0.00012087, 0.0049642],
+    [0.00144616, 0.00015564, 0.0064067],
+    [0.00185359, 0.0001992, 0.0082193],
+    [0.0023616, 0.0002534, 0.0104822],
+    [0.0029906, 0.0003202, 0.013289],
+    [0.0037645, 0.0004024, 0.016747],
+    [0.0047102, 0.0005023, 0.02098],
+    [0.0058581, 0.0006232, 0.026127],
+    [0.0072423, 0.0007685, 0.032344],
+    [0.0088996, 0.0009417, 0.039802],
+    [0.0108709, 0.0011478, 0.048691],
+    [0.0131989, 0.0013903, 0.05921],
+    [0.0159292, 0.001674, 0.071576],
+    [0.0191097, 0.0020044, 0.0860109],
+    [0.022788, 0.002386, 0.10274],
+    [0.027011, 0.002822, 0.122],
+    [0.031829, 0.003319, 0.14402],
+    [0.037278, 0.00388, 0.16899],
+    [0.0434, 0.004509, 0.19712],
+    [0.050223, 0.005209, 0.22857],
+    [0.057764, 0.005985, 0.26347],
+    [0.066038, 0.006833, 0.3019],
+    [0.075033, 0.007757, 0.34387],
+    [0.084736, 0.008756, 0.389366],
+    [0.095041, 0.009816, 0.43797],
+    [0.105836, 0.010918, 0.48922],
+    [0.117066, 0.012058, 0.5429],
+    [0.128682, 0.013237, 0.59881],
+    [0.140638, 0.014456, 0.65676],
+    [0.152893, 0.015717, 0.71658],
+    [0.165416, 0.017025, 0.77812],
+    [0.178191, 0.018399, 0.84131],
+    [0.191214, 0.019848, 0.90611],
+    [0.204492, 0.021391, 0.972542],
+    [0.21765, 0.022992, 1.0389],
+    [0.230267, 0.024598, 1.1031],
+    [0.242311, 0.026213, 1.1651],
+    [0.253793, 0.027841, 1.2249],
+    [0.264737, 0.029497, 1.2825],
+    [0.275195, 0.031195, 1.3382],
+    [0.285301, 0.032927, 1.3926],
+    [0.295143, 0.034738, 1.4461],
+    [0.304869, 0.036654, 1.4994],
+    [0.314679, 0.038676, 1.55348],
+    [0.324355, 0.040792, 1.6072],
+    [0.33357, 0.042946, 1.6589],
+    [0.342243, 0.045114, 1.7082],
+    [0.350312, 0.047333, 1.7548],
+    [0.357719, 0.049602, 1.7985],
+    [0.364482, 0.051934, 1.8392],
+    [0.370493, 0.054337, 1.8766],
+    [0.375727, 0.056822, 1.9105],
+    [0.380158, 0.059399, 1.9408],
+    [0.383734, 0.062077, 1.96728],
+    [0.386327, 0.064737, 1.9891],
+    [0.387858, 0.067285, 2.0057],
+    [0.388396, 0.069764, 2.0174],
+    [0.387978, 0.072218, 2.0244],
+    [0.386726, 0.074704, 2.0273],
+    [0.384696, 0.077272, 2.0264],
+    [0.382006, 0.079979, 2.0223],
+    [0.378709, 0.082874, 2.0153],
+    [0.374915, 0.086, 2.006],
+    [0.370702, 0.089456, 1.9948],
+    [0.366089, 0.092947, 1.9814],
+    [0.361045, 0.096275, 1.9653],
+    [0.355518, 0.099535, 1.9464],
+    [0.349486, 0.102829, 1.9248],
+    [0.342957, 0.106256, 1.9007],
+    [0.335893, 0.109901, 1.8741],
+    [0.328284, 0.113835, 1.8451],
+    [0.32015, 0.118167, 1.8139],
+    [0.311475, 0.122932, 1.7806],
+    [0.302273, 0.128201, 1.74537],
+    [0.292858, 0.133457, 1.7091],
+    [0.283502, 0.138323, 1.6723],
+    [0.274044, 0.143042, 1.6347],
+    [0.264263, 0.147787, 1.5956],
+    [0.254085, 0.152761, 1.5549],
+    [0.243392, 0.158102, 1.5122],
+    [0.232187, 0.163941, 1.4673],
+    [0.220488, 0.170362, 1.4199],
+    [0.208198, 0.177425, 1.37],
+    [0.195618, 0.18519, 1.31756],
+    [0.183034, 0.193025, 1.2624],
+    [0.170222, 0.200313, 1.205],
+    [0.157348, 0.207156, 1.1466],
+    [0.14465, 0.213644, 1.088],
+    [0.132349, 0.21994, 1.0302],
+    [0.120584, 0.22617, 0.97383],
+    [0.109456, 0.232467, 0.91943],
+    [0.099042, 0.239025, 0.86746],
+    [0.089388, 0.245997, 0.81828],
+    [0.080507, 0.253589, 0.772125],
+    [0.072034, 0.261876, 0.72829],
+    [0.06371, 0.270643, 0.68604],
+    [0.055694, 0.279645, 0.64553],
+    [0.048117, 0.288694, 0.60685],
+    [0.041072, 0.297665, 0.57006],
+    [0.034642, 0.306469, 0.53522],
+    [0.028896, 0.315035, 0.50234],
+    [0.023876, 0.323335, 0.4714],
+    [0.019628, 0.331366, 0.44239],
+    [0.016172, 0.339133, 0.415254],
+    [0.0133, 0.34786, 0.390024],
+    [0.010759, 0.358326, 0.366399],
+    [0.008542, 0.370001, 0.344015],
+    [0.006661, 0.382464, 0.322689],
+    [0.005132, 0.395379, 0.302356],
+    [0.003982, 0.408482, 0.283036],
+    [0.003239, 0.421588, 0.264816],
+    [0.002934, 0.434619, 0.247848],
+    [0.003114, 0.447601, 0.232318],
+    [0.003816, 0.460777, 0.218502],
+    [0.005095, 0.47434, 0.205851],
+    [0.006936, 0.4882, 0.193596],
+    [0.009299, 0.50234, 0.181736],
+    [0.012147, 0.51674, 0.170281],
+    [0.015444, 0.53136, 0.159249],
+    [0.019156, 0.54619, 0.148673],
+    [0.02325, 0.56118, 0.138609],
+    [0.02769, 0.57629, 0.129096],
+    [0.032444, 0.5915, 0.120215],
+    [0.037465, 0.606741, 0.112044],
+    [0.042956, 0.62215, 0.10471],
+    [0.049114, 0.63783, 0.098196],
+    [0.05592, 0.65371, 0.092361],
+    [0.063349, 0.66968, 0.087088],
+    [0.071358, 0.68566, 0.082248],
+    [0.079901, 0.70155, 0.077744],
+    [0.088909, 0.71723, 0.073456],
+    [0.098293, 0.73257, 0.069268],
+    [0.107949, 0.74746, 0.06506],
+    [0.117749, 0.761757, 0.060709],
+    [0.127839, 0.77534, 0.056457],
+    [0.13845, 0.78822, 0.052609],
+    [0.149516, 0.80046, 0.049122],
+    [0.161041, 0.81214, 0.045954],
+    [0.172953, 0.82333, 0.04305],
+    [0.185209, 0.83412, 0.040368],
+    [0.197755, 0.8446, 0.037839],
+    [0.210538, 0.85487, 0.035384],
+    [0.22346, 0.86504, 0.032949],
+    [0.236491, 0.875211, 0.030451],
+    [0.249633, 0.88537, 0.028029],
+    [0.262972, 0.89537, 0.025862],
+    [0.276515, 0.90515, 0.02392],
+    [0.290269, 0.91465, 0.022174],
+    [0.304213, 0.92381, 0.020584],
+    [0.318361, 0.93255, 0.019127],
+    [0.332705, 0.94081, 0.01774],
+    [0.347232, 0.94852, 0.016403],
+    [0.361926, 0.9556, 0.015064],
+    [0.376772, 0.961988, 0.013676],
+    [0.391683, 0.96754, 0.012308],
+    [0.406594, 0.97223, 0.011056],
+    [0.421539, 0.97617, 0.009915],
+    [0.436517, 0.97946, 0.008872],
+    [0.451584, 0.9822, 0.007918],
+    [0.466782, 0.98452, 0.00703],
+    [0.482147, 0.98652, 0.006223],
+    [0.497738, 0.98832, 0.005453],
+    [0.513606, 0.99002, 0.004714],
+    [0.529826, 0.991761, 0.003988],
+    [0.54644, 0.99353, 0.003289],
+    [0.563426, 0.99523, 0.002646],
+    [0.580726, 0.99677, 0.002063],
+    [0.59829, 0.99809, 0.001533],
+    [0.616053, 0.99911, 0.001091],
+    [0.633948, 0.99977, 0.000711],
+    [0.651901, 1.0, 0.000407],
+    [0.669824, 0.99971, 0.000184],
+    [0.687632, 0.99885, 0.000047],
+    [0.705224, 0.99734, 0.0],
+    [0.722773, 0.99526, 0.0],
+    [0.740483, 0.99274, 0.0],
+    [0.758273, 0.98975, 0.0],
+    [0.776083, 0.9863, 0.0],
+    [0.793832, 0.98238, 0.0],
+    [0.811436, 0.97798, 0.0],
+    [0.828822, 0.97311, 0.0],
+    [0.845879, 0.96774, 0.0],
+    [0.862525, 0.96189, 0.0],
+    [0.878655, 0.955552, 0.0],
+    [0.894208, 0.948601, 0.0],
+    [0.909206, 0.940981, 0.0],
+    [0.923672, 0.932798, 0.0],
+    [0.937638, 0.924158, 0.0],
+    [0.951162, 0.915175, 0.0],
+    [0.964283, 0.905954, 0.0],
+    [0.977068, 0.896608, 0.0],
+    [0.98959, 0.887249, 0.0],
+    [1.00191, 0.877986, 0.0],
+    [1.01416, 0.868934, 0.0],
+    [1.0265, 0.860164, 0.0],
+    [1.0388, 0.851519, 0.0],
+    [1.051, 0.842963, 0.0],
+    [1.0629, 0.834393, 0.0],
+    [1.0743, 0.825623, 0.0],
+    [1.0852, 0.816764, 0.0],
+    [1.0952, 0.807544, 0.0],
+    [1.1042, 0.797947, 0.0],
+    [1.112, 0.787893, 0.0],
+    [1.11852, 0.777405, 0.0],
+    [1.1238, 0.76649, 0.0],
+    [1.128, 0.755309, 0.0],
+    [1.1311, 0.743845, 0.0],
+    [1.1332, 0.73219, 0.0],
+    [1.1343, 0.720353, 0.0],
+    [1.1343, 0.708281, 0.0],
+    [1.1333, 0.696055, 0.0],
+    [1.1312, 0.683621, 0.0],
+    [1.1281, 0.671048, 0.0],
+    [1.12399, 0.658341, 0.0],
+    [1.1189, 0.645545, 0.0],
+    [1.1129, 0.632718, 0.0],
+    [1.1059, 0.619815, 0.0],
+    [1.098, 0.606887, 0.0],
+    [1.0891, 0.593878, 0.0],
+    [1.0792, 0.580781, 0.0],
+    [1.0684, 0.567653, 0.0],
+    [1.0567, 0.55449, 0.0],
+    [1.044, 0.541228, 0.0],
+    [1.03048, 0.527963, 0.0],
+    [1.016, 0.514634, 0.0],
+    [1.0008, 0.501363, 0.0],
+    [0.98479, 0.488124, 0.0],
+    [0.96808, 0.474935, 0.0],
+    [0.95074, 0.461834, 0.0],
+    [0.9328, 0.448823, 0.0],
+    [0.91434, 0.435917, 0.0],
+    [0.89539, 0.423153, 0.0],
+    [0.87603, 0.410526, 0.0],
+    [0.856297, 0.398057, 0.0],
+    [0.83635, 0.385835, 0.0],
+    [0.81629, 0.373951, 0.0],
+    [0.79605, 0.362311, 0.0],
+    [0.77561, 0.350863, 0.0],
+    [0.75493, 0.339554, 0.0],
+    [0.73399, 0.328309, 0.0],
+    [0.71278, 0.317118, 0.0],
+    [0.69129, 0.305936, 0.0],
+    [0.66952, 0.294737, 0.0],
+    [0.647467, 0.283493, 0.0],
+    [0.62511, 0.272222, 0.0],
+    [0.60252, 0.26099, 0.0],
+    [0.57989, 0.249877, 0.0],
+    [0.55737, 0.238946, 0.0],
+    [0.53511, 0.228254, 0.0],
+    [0.51324, 0.217853, 0.0],
+    [0.49186, 0.20778, 0.0],
+    [0.47108, 0.198072, 0.0],
+    [0.45096, 0.188748, 0.0],
+    [0.431567, 0.179828, 0.0],
+    [0.41287, 0.171285, 0.0],
+    [0.39475, 0.163059, 0.0],
+    [0.37721, 0.155151, 0.0],
+    [0.36019, 0.147535, 0.0],
+    [0.34369, 0.140211, 0.0],
+    [0.32769, 0.13317, 0.0],
+    [0.31217, 0.1264, 0.0],
+    [0.29711, 0.119892, 0.0],
+    [0.2825, 0.11364, 0.0],
+    [0.268329, 0.107633, 0.0],
+    [0.25459, 0.10187, 0.0],
+    [0.2413, 0.096347, 0.0],
+    [0.22848, 0.091063, 0.0],
+    [0.21614, 0.08601, 0.0],
+    [0.2043, 0.081187, 0.0],
+    [0.19295, 0.076583, 0.0],
+    [0.18211, 0.072198, 0.0],
+    [0.17177, 0.068024, 0.0],
+    [0.16192, 0.064052, 0.0],
+    [0.152568, 0.060281, 0.0],
+    [0.14367, 0.056697, 0.0],
+    [0.1352, 0.053292, 0.0],
+    [0.12713, 0.050059, 0.0],
+    [0.11948, 0.046998, 0.0],
+    [0.11221, 0.044096, 0.0],
+    [0.10531, 0.041345, 0.0],
+    [0.098786, 0.0387507, 0.0],
+    [0.09261, 0.0362978, 0.0],
+    [0.086773, 0.0339832, 0.0],
+    [0.0812606, 0.0318004, 0.0],
+    [0.076048, 0.0297395, 0.0],
+    [0.071114, 0.0277918, 0.0],
+    [0.066454, 0.0259551, 0.0],
+    [0.062062, 0.0242263, 0.0],
+    [0.05793, 0.0226017, 0.0],
+    [0.05405, 0.0210779, 0.0],
+    [0.050412, 0.0196505, 0.0],
+    [0.047006, 0.0183153, 0.0],
+    [0.043823, 0.0170686, 0.0],
+    [0.0408508, 0.0159051, 0.0],
+    [0.038072, 0.0148183, 0.0],
+    [0.035468, 0.0138008, 0.0],
+    [0.033031, 0.0128495, 0.0],
+    [0.030753, 0.0119607, 0.0],
+    [0.028623, 0.0111303, 0.0],
+    [0.026635, 0.0103555, 0.0],
+    [0.024781, 0.0096332, 0.0],
+    [0.023052, 0.0089599, 0.0],
+    [0.021441, 0.0083324, 0.0],
+    [0.0199413, 0.0077488, 0.0],
+    [0.018544, 0.0072046, 0.0],
+    [0.017241, 0.0066975, 0.0],
+    [0.016027, 0.0062251, 0.0],
+    [0.014896, 0.005785, 0.0],
+    [0.013842, 0.0053751, 0.0],
+    [0.012862, 0.0049941, 0.0],
+    [0.011949, 0.0046392, 0.0],
+    [0.0111, 0.0043093, 0.0],
+    [0.010311, 0.0040028, 0.0],
+    [0.00957688, 0.00371774, 0.0],
+    [0.008894, 0.00345262, 0.0],
+    [0.0082581, 0.00320583, 0.0],
+    [0.0076664, 0.00297623, 0.0],
+    [0.0071163, 0.00276281, 0.0],
+    [0.0066052, 0.00256456, 0.0],
+    [0.0061306, 0.00238048, 0.0],
+    [0.0056903, 0.00220971, 0.0],
+    [0.0052819, 0.00205132, 0.0],
+    [0.0049033, 0.00190449, 0.0],
+    [0.00455263, 0.00176847, 0.0],
+    [0.0042275, 0.00164236, 0.0],
+    [0.0039258, 0.00152535, 0.0],
+    [0.0036457, 0.00141672, 0.0],
+    [0.0033859, 0.00131595, 0.0],
+    [0.0031447, 0.00122239, 0.0],
+    [0.0029208, 0.00113555, 0.0],
+    [0.002713, 0.00105494, 0.0],
+    [0.0025202, 0.00098014, 0.0],
+    [0.0023411, 0.00091066, 0.0],
+    [0.00217496, 0.00084619, 0.0],
+    [0.0020206, 0.00078629, 0.0],
+    [0.0018773, 0.00073068, 0.0],
+    [0.0017441, 0.00067899, 0.0],
+    [0.0016205, 0.00063101, 0.0],
+    [0.0015057, 0.00058644, 0.0],
+    [0.0013992, 0.00054511, 0.0],
+    [0.0013004, 0.00050672, 0.0],
+    [0.0012087, 0.00047111, 0.0],
+    [0.0011236, 0.00043805, 0.0],
+    [0.00104476, 0.00040741, 0.0],
+    [0.00097156, 0.000378962, 0.0],
+    [0.0009036, 0.000352543, 0.0],
+    [0.00084048, 0.000328001, 0.0],
+    [0.00078187, 0.000305208, 0.0],
+    [0.00072745, 0.000284041, 0.0],
+    [0.0006769, 0.000264375, 0.0],
+    [0.00062996, 0.000246109, 0.0],
+    [0.00058637, 0.000229143, 0.0],
+    [0.00054587, 0.000213376, 0.0],
+    [0.000508258, 0.00019873, 0.0],
+    [0.0004733, 0.000185115, 0.0],
+    [0.0004408, 0.000172454, 0.0],
+    [0.00041058, 0.000160678, 0.0],
+    [0.00038249, 0.00014973, 0.0],
+    [0.00035638, 0.00013955, 0.0],
+    [0.00033211, 0.000130086, 0.0],
+    [0.00030955, 0.00012129, 0.0],
+    [0.00028858, 0.000113106, 0.0],
+    [0.00026909, 0.000105501, 0.0],
+    [0.000250969, 0.000098428, 0.0],
+    [0.00023413, 0.000091853, 0.0],
+    [0.00021847, 0.000085738, 0.0],
+    [0.00020391, 0.000080048, 0.0],
+    [0.00019035, 0.000074751, 0.0],
+    [0.00017773, 0.000069819, 0.0],
+    [0.00016597, 0.000065222, 0.0],
+    [0.00015502, 0.000060939, 0.0],
+    [0.0001448, 0.000056942, 0.0],
+    [0.00013528, 0.000053217, 0.0],
+    [0.00012639, 0.000049737, 0.0],
+    [0.0001181, 0.000046491, 0.0],
+    [0.00011037, 0.000043464, 0.0],
+    [0.00010315, 0.000040635, 0.0],
+    [0.000096427, 0.000038, 0.0],
+    [0.000090151, 0.0000355405, 0.0],
+    [0.000084294, 0.0000332448, 0.0],
+    [0.00007883, 0.0000311006, 0.0],
+    [0.000073729, 0.000029099, 0.0],
+    [0.000068969, 0.0000272307, 0.0],
+    [0.0000645258, 0.000025486, 0.0],
+    [0.000060376, 0.0000238561, 0.0],
+    [0.0000565, 0.0000223332, 0.0],
+    [0.00005288, 0.0000209104, 0.0],
+    [0.000049498, 0.0000195808, 0.0],
+    [0.000046339, 0.0000183384, 0.0],
+    [0.000043389, 0.0000171777, 0.0],
+    [0.000040634, 0.0000160934, 0.0],
+    [0.00003806, 0.00001508, 0.0],
+    [0.000035657, 0.0000141336, 0.0],
+    [0.0000334117, 0.000013249, 0.0],
+];
+
+/// CIE 13.3 test color samples TCS01-TCS14, spectral reflectance at 5nm steps.
+pub const TCS: [[f64; 81]; 14] = [
+    [
+        0.219, 0.239, 0.252, 0.256, 0.256, 0.254, 0.252, 0.248, 0.244, 0.24, 0.237, 0.232, 0.23,
+        0.226, 0.225, 0.222, 0.22, 0.218, 0.216, 0.214, 0.214, 0.214, 0.216, 0.218, 0.223, 0.225,
+        0.226, 0.226, 0.225, 0.225, 0.227, 0.23, 0.236, 0.245, 0.253, 0.262, 0.272, 0.283, 0.298,
+        0.318, 0.341, 0.367, 0.39, 0.409, 0.424, 0.435, 0.442, 0.448, 0.45, 0.451, 0.451, 0.451,
+        0.451, 0.451, 0.45, 0.45, 0.451, 0.451, 0.453, 0.454, 0.455, 0.457, 0.458, 0.46, 0.462,
+        0.463, 0.464, 0.465, 0.466, 0.466, 0.466, 0.466, 0.467, 0.467, 0.467, 0.467, 0.467, 0.467,
+        0.467, 0.467, 0.467,
+    ],
+    [
+        0.07, 0.079, 0.089, 0.101, 0.111, 0.116, 0.118, 0.12, 0.121, 0.122, 0.122, 0.122, 0.123,
+        0.124, 0.127, 0.128, 0.131, 0.134, 0.138, 0.143, 0.15, 0.159, 0.174, 0.19, 0.207, 0.225,
+        0.242, 0.253, 0.26, 0.264, 0.267, 0.269, 0.272, 0.276, 0.282, 0.289, 0.299, 0.309, 0.322,
+        0.329, 0.335, 0.339, 0.341, 0.341, 0.342, 0.342, 0.342, 0.341, 0.341, 0.339, 0.339, 0.338,
+        0.338, 0.337, 0.336, 0.335, 0.334, 0.332, 0.332, 0.331, 0.331, 0.33, 0.329, 0.328, 0.328,
+        0.327, 0.326, 0.325, 0.324, 0.324, 0.324, 0.323, 0.322, 0.321, 0.32, 0.318, 0.316, 0.315,
+        0.315, 0.314, 0.314,
+    ],
+    [
+        0.065, 0.068, 0.07, 0.072, 0.073, 0.073, 0.074, 0.074, 0.074, 0.073, 0.073, 0.073, 0.073,
+        0.073, 0.074, 0.075, 0.077, 0.08, 0.085, 0.094, 0.109, 0.126, 0.148, 0.172, 0.198, 0.221,
+        0.241, 0.26, 0.278, 0.302, 0.339, 0.37, 0.392, 0.399, 0.4, 0.393, 0.38, 0.365, 0.349,
+        0.332, 0.315, 0.299, 0.285, 0.272, 0.264, 0.257, 0.252, 0.247, 0.241, 0.235, 0.229, 0.224,
+        0.22, 0.217, 0.216, 0.216, 0.219, 0.224, 0.23, 0.238, 0.251, 0.269, 0.288, 0.312, 0.34,
+        0.366, 0.39, 0.412, 0.431, 0.447, 0.46, 0.472, 0.481, 0.488, 0.493, 0.497, 0.5, 0.502,
+        0.505, 0.51, 0.516,
+    ],
+    [
+        0.074, 0.083, 0.093, 0.105, 0.116, 0.121, 0.124, 0.126, 0.128, 0.131, 0.135, 0.139, 0.144,
+        0.151, 0.161, 0.172, 0.186, 0.205, 0.229, 0.254, 0.281, 0.308, 0.332, 0.352, 0.37, 0.383,
+        0.39, 0.394, 0.395, 0.392, 0.385, 0.377, 0.367, 0.354, 0.341, 0.327, 0.312, 0.296, 0.28,
+        0.263, 0.247, 0.229, 0.214, 0.198, 0.185, 0.175, 0.169, 0.164, 0.16, 0.156, 0.154, 0.152,
+        0.151, 0.149, 0.148, 0.148, 0.148, 0.149, 0.151, 0.154, 0.158, 0.162, 0.165, 0.168, 0.17,
+        0.171, 0.17, 0.168, 0.166, 0.164, 0.164, 0.165, 0.168, 0.172, 0.177, 0.181, 0.185, 0.189,
+        0.192, 0.194, 0.197,
+    ],
+    [
+        0.295, 0.306, 0.31, 0.312, 0.313, 0.315, 0.319, 0.322, 0.326, 0.33, 0.334, 0.339, 0.346,
+        0.352, 0.36, 0.369, 0.381, 0.394, 0.403, 0.41, 0.415, 0.418, 0.419, 0.417, 0.413, 0.409,
+        0.403, 0.396, 0.389, 0.381, 0.372, 0.363, 0.353, 0.342, 0.331, 0.32, 0.308, 0.296, 0.284,
+        0.271, 0.26, 0.247, 0.232, 0.22, 0.21, 0.2, 0.194, 0.189, 0.185, 0.183, 0.18, 0.177, 0.176,
+        0.175, 0.175, 0.175, 0.175, 0.177, 0.18, 0.183, 0.186, 0.189, 0.192, 0.195, 0.199, 0.2,
+        0.199, 0.198, 0.196, 0.195, 0.195, 0.196, 0.197, 0.2, 0.203, 0.205, 0.208, 0.212, 0.215,
+        0.217, 0.219,
+    ],
+    [
+        0.151, 0.203, 0.265, 0.339, 0.41, 0.464, 0.492, 0.508, 0.517, 0.524, 0.531, 0.538, 0.544,
+        0.551, 0.556, 0.556, 0.554, 0.549, 0.541, 0.531, 0.519, 0.504, 0.488, 0.469, 0.45, 0.431,
+        0.414, 0.395, 0.377, 0.358, 0.341, 0.325, 0.309, 0.293, 0.279, 0.265, 0.253, 0.241, 0.234,
+        0.227, 0.225, 0.222, 0.221, 0.22, 0.22, 0.22, 0.22, 0.22, 0.223, 0.227, 0.233, 0.239,
+        0.244, 0.251, 0.258, 0.263, 0.268, 0.273, 0.278, 0.281, 0.283, 0.286, 0.291, 0.296, 0.302,
+        0.313, 0.325, 0.338, 0.351, 0.364, 0.376, 0.389, 0.401, 0.413, 0.425, 0.436, 0.447, 0.458,
+        0.469, 0.477, 0.485,
+    ],
+    [
+        0.378, 0.459, 0.524, 0.546, 0.551, 0.555, 0.559, 0.56, 0.561, 0.558, 0.556, 0.551, 0.544,
+        0.535, 0.522, 0.506, 0.488, 0.469, 0.448, 0.429, 0.408, 0.385, 0.363, 0.341, 0.324, 0.311,
+        0.301, 0.291, 0.283, 0.273, 0.265, 0.26, 0.257, 0.257, 0.259, 0.26, 0.26, 0.258, 0.256,
+        0.254, 0.254, 0.259, 0.27, 0.284, 0.302, 0.324, 0.344, 0.362, 0.377, 0.389, 0.4, 0.41,
+        0.42, 0.429, 0.438, 0.445, 0.452, 0.457, 0.462, 0.466, 0.468, 0.47, 0.473, 0.477, 0.483,
+        0.489, 0.496, 0.503, 0.511, 0.518, 0.525, 0.532, 0.539, 0.546, 0.553, 0.559, 0.565, 0.57,
+        0.575, 0.578, 0.581,
+    ],
+    [
+        0.104, 0.129, 0.17, 0.24, 0.319, 0.416, 0.462, 0.482, 0.49, 0.488, 0.482, 0.473, 0.462,
+        0.45, 0.439, 0.426, 0.413, 0.397, 0.382, 0.366, 0.352, 0.337, 0.325, 0.31, 0.299, 0.289,
+        0.283, 0.276, 0.27, 0.262, 0.256, 0.251, 0.25, 0.251, 0.254, 0.258, 0.264, 0.269, 0.272,
+        0.274, 0.278, 0.284, 0.295, 0.316, 0.348, 0.384, 0.434, 0.482, 0.528, 0.568, 0.604, 0.629,
+        0.648, 0.663, 0.676, 0.685, 0.693, 0.7, 0.705, 0.709, 0.712, 0.715, 0.717, 0.719, 0.721,
+        0.72, 0.719, 0.722, 0.725, 0.727, 0.729, 0.73, 0.73, 0.73, 0.73, 0.73, 0.73, 0.73, 0.73,
+        0.73, 0.73,
+    ],
+    [
+        0.066, 0.062, 0.058, 0.055, 0.052, 0.052, 0.051, 0.05, 0.05, 0.049, 0.048, 0.047, 0.046,
+        0.044, 0.042, 0.041, 0.038, 0.035, 0.033, 0.031, 0.03, 0.029, 0.028, 0.028, 0.028, 0.029,
+        0.03, 0.03, 0.031, 0.031, 0.032, 0.032, 0.033, 0.034, 0.035, 0.037, 0.041, 0.044, 0.048,
+        0.052, 0.06, 0.076, 0.102, 0.136, 0.19, 0.256, 0.336, 0.418, 0.505, 0.581, 0.641, 0.682,
+        0.717, 0.74, 0.758, 0.77, 0.781, 0.79, 0.797, 0.803, 0.809, 0.814, 0.819, 0.824, 0.828,
+        0.83, 0.831, 0.833, 0.835, 0.836, 0.836, 0.837, 0.838, 0.839, 0.839, 0.839, 0.839, 0.839,
+        0.839, 0.839, 0.839,
+    ],
+    [
+        0.05, 0.054, 0.059, 0.063, 0.066, 0.067, 0.068, 0.069, 0.069, 0.07, 0.072, 0.073, 0.076,
+        0.078, 0.083, 0.088, 0.095, 0.103, 0.113, 0.125, 0.142, 0.162, 0.189, 0.219, 0.262, 0.305,
+        0.365, 0.416, 0.465, 0.509, 0.546, 0.581, 0.61, 0.634, 0.653, 0.666, 0.678, 0.687, 0.693,
+        0.698, 0.701, 0.704, 0.705, 0.705, 0.706, 0.707, 0.707, 0.707, 0.708, 0.708, 0.71, 0.711,
+        0.712, 0.714, 0.716, 0.718, 0.72, 0.722, 0.725, 0.729, 0.731, 0.735, 0.739, 0.742, 0.746,
+        0.748, 0.749, 0.751, 0.753, 0.754, 0.755, 0.755, 0.755, 0.755, 0.756, 0.757, 0.758, 0.759,
+        0.759, 0.759, 0.759,
+    ],
+    [
+        0.111, 0.121, 0.127, 0.129, 0.127, 0.121, 0.116, 0.112, 0.108, 0.105, 0.104, 0.104, 0.105,
+        0.106, 0.11, 0.115, 0.123, 0.134, 0.148, 0.167, 0.192, 0.219, 0.252, 0.291, 0.325, 0.347,
+        0.356, 0.353, 0.346, 0.333, 0.314, 0.294, 0.271, 0.248, 0.227, 0.206, 0.188, 0.17, 0.153,
+        0.138, 0.125, 0.114, 0.106, 0.1, 0.096, 0.092, 0.09, 0.087, 0.085, 0.082, 0.08, 0.079,
+        0.078, 0.078, 0.078, 0.078, 0.081, 0.083, 0.088, 0.093, 0.102, 0.112, 0.125, 0.141, 0.161,
+        0.182, 0.203, 0.223, 0.242, 0.257, 0.27, 0.282, 0.292, 0.302, 0.31, 0.314, 0.317, 0.323,
+        0.33, 0.334, 0.338,
+    ],
+    [
+        0.12, 0.103, 0.09, 0.082, 0.076, 0.068, 0.064, 0.065, 0.075, 0.093, 0.123, 0.16, 0.207,
+        0.256, 0.3, 0.331, 0.346, 0.347, 0.341, 0.328, 0.307, 0.282, 0.257, 0.23, 0.204, 0.178,
+        0.154, 0.129, 0.109, 0.09, 0.075, 0.062, 0.051, 0.041, 0.035, 0.029, 0.025, 0.022, 0.019,
+        0.017, 0.017, 0.017, 0.016, 0.016, 0.016, 0.016, 0.016, 0.016, 0.016, 0.016, 0.018, 0.018,
+        0.018, 0.018, 0.019, 0.02, 0.023, 0.024, 0.026, 0.03, 0.035, 0.043, 0.056, 0.074, 0.097,
+        0.128, 0.166, 0.21, 0.257, 0.305, 0.354, 0.401, 0.446, 0.485, 0.52, 0.551, 0.577, 0.599,
+        0.618, 0.633, 0.645,
+    ],
+    [
+        0.104, 0.127, 0.161, 0.211, 0.264, 0.313, 0.341, 0.352, 0.359, 0.361, 0.364, 0.365, 0.367,
+        0.369, 0.372, 0.374, 0.376, 0.379, 0.384, 0.389, 0.397, 0.405, 0.416, 0.429, 0.443, 0.454,
+        0.461, 0.466, 0.469, 0.471, 0.474, 0.476, 0.483, 0.49, 0.506, 0.526, 0.553, 0.582, 0.618,
+        0.651, 0.68, 0.701, 0.717, 0.729, 0.736, 0.742, 0.745, 0.747, 0.748, 0.748, 0.748, 0.748,
+        0.748, 0.748, 0.748, 0.748, 0.747, 0.747, 0.747, 0.747, 0.747, 0.747, 0.747, 0.746, 0.746,
+        0.746, 0.745, 0.744, 0.743, 0.744, 0.745, 0.748, 0.75, 0.75, 0.749, 0.748, 0.748, 0.747,
+        0.747, 0.747, 0.747,
+    ],
+    [
+        0.036, 0.036, 0.037, 0.038, 0.039, 0.039, 0.04, 0.041, 0.042, 0.042, 0.043, 0.044, 0.044,
+        0.045, 0.045, 0.046, 0.047, 0.048, 0.05, 0.052, 0.055, 0.057, 0.062, 0.067, 0.075, 0.083,
+        0.092, 0.1, 0.108, 0.121, 0.133, 0.142, 0.15, 0.154, 0.155, 0.152, 0.147, 0.14, 0.133,
+        0.125, 0.118, 0.112, 0.106, 0.101, 0.098, 0.095, 0.093, 0.09, 0.089, 0.087, 0.086, 0.085,
+        0.084, 0.084, 0.084, 0.084, 0.085, 0.087, 0.092, 0.096, 0.102, 0.11, 0.123, 0.137, 0.152,
+        0.169, 0.188, 0.207, 0.226, 0.243, 0.26, 0.277, 0.294, 0.31, 0.325, 0.339, 0.353, 0.366,
+        0.379, 0.39, 0.399,
+    ],
+];
+
+/// CIE daylight components S0, S1 and S2 at 5nm steps.
+pub const CIE_D_S: [[f64; 81]; 3] = [
+    [
+        63.4, 64.6, 65.8, 80.3, 94.8, 99.8, 104.8, 105.35, 105.9, 101.35, 96.8, 105.35, 113.9,
+        119.75, 125.6, 125.55, 125.5, 123.4, 121.3, 121.3, 121.3, 117.4, 113.5, 113.3, 113.1,
+        111.95, 110.8, 108.65, 106.5, 107.65, 108.8, 107.05, 105.3, 104.85, 104.4, 102.2, 100.0,
+        98.0, 96.0, 95.55, 95.1, 92.1, 89.1, 89.8, 90.5, 90.4, 90.3, 89.35, 88.4, 86.2, 84.0,
+        84.55, 85.1, 83.5, 81.9, 82.25, 82.6, 83.75, 84.9, 83.1, 81.3, 76.6, 71.9, 73.1, 74.3,
+        75.35, 76.4, 69.85, 63.3, 67.5, 71.7, 74.35, 77.0, 71.1, 65.2, 56.45, 47.7, 58.15, 68.6,
+        66.8, 65.0,
+    ],
+    [
+        38.5, 36.75, 35.0, 39.2, 43.4, 44.85, 46.3, 45.1, 43.9, 40.5, 37.1, 36.9, 36.7, 36.3, 35.9,
+        34.25, 32.6, 30.25, 27.9, 26.1, 24.3, 22.2, 20.1, 18.15, 16.2, 14.7, 13.2, 10.9, 8.6, 7.35,
+        6.1, 5.15, 4.2, 3.05, 1.9, 0.95, 0.0, -0.8, -1.6, -2.55, -3.5, -3.5, -3.5, -4.65, -5.8,
+        -6.5, -7.2, -7.9, -8.6, -9.05, -9.5, -10.2, -10.9, -10.8, -10.7, -11.35, -12.0, -13.0,
+        -14.0, -13.8, -13.6, -12.8, -12.0, -12.65, -13.3, -13.1, -12.9, -11.75, -10.6, -11.1,
+        -11.6, -11.9, -12.2, -11.2, -10.2, -9.0, -7.8, -9.5, -11.2, -10.8, -10.4,
+    ],
+    [
+        3.0, 2.1, 1.2, 0.05, -1.1, -0.8, -0.5, -0.6, -0.7, -0.95, -1.2, -1.9, -2.6, -2.75, -2.9,
+        -2.85, -2.8, -2.7, -2.6, -2.6, -2.6, -2.2, -1.8, -1.65, -1.5, -1.4, -1.3, -1.25, -1.2,
+        -1.1, -1.0, -0.75, -0.5, -0.4, -0.3, -0.15, 0.0, 0.1, 0.2, 0.35, 0.5, 1.3, 2.1, 2.65, 3.2,
+        3.65, 4.1, 4.4, 4.7, 4.9, 5.1, 5.9, 6.7, 7.0, 7.3, 7.95, 8.6, 9.2, 9.8, 10.0, 10.2, 9.25,
+        8.3, 8.95, 9.6, 9.05, 8.5, 7.75, 7.0, 7.3, 7.6, 7.8, 8.0, 7.35, 6.7, 5.95, 5.2, 6.3, 7.4,
+        7.1, 6.8,
+    ],
+];
@@ -0,0 +1,129 @@
+//! Which field of the MR response is where, for firmware that might order
+//! them differently.
+//!
+//! [`CaptureInfo::parse_with`](crate::CaptureInfo::parse_with) reads the
+//! comma separated tokens of the response in the order a [`FieldLayout`]
+//! lists them, so supporting a firmware that moves fields around means adding
+//! a layout here rather than changing the parser:
+//!
+//! 1. record a session with the new firmware (`--record`) and work out the
+//!    order its MR response has the fields in
+//! 2. add a `const` [`FieldLayout`] listing them in that order, with
+//!    `firmware` set to the start of the version strings it's for
+//! 3. add it to `LAYOUTS`, which [`FieldLayout::for_firmware`] searches
+//!
+//! Only the order can change, each [`MrField`] is always read the same way.
+//! A field that's encoded differently needs a new variant.
+
+use crate::FirmwareVersion;
+
+/// One field of the MR response, or a run of tokens read together like the
+/// 15 CRI values. The `Unk` ones aren't decoded yet, but still have to be
+/// stepped over, and are kept for the Debug output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MrField {
+    Unk0,
+    Title,
+    Unk1,
+    Unk2,
+    Unk3,
+    Unk4,
+    Unk5,
+    Unk6,
+    Unk7,
+    Unk8,
+    CctK,
+    Duv,
+    Unk11,
+    /// Six raw tokens.
+    Unks,
+    IlluminanceLx,
+    IlluminanceFc,
+    TristimulusX,
+    TristimulusY,
+    TristimulusZ,
+    Cie1931X,
+    Cie1931Y,
+    Cie1976Up,
+    Unk12,
+    Unk13,
+    Cie1976Vp,
+    DominantWavelength,
+    Purity,
+    CriRa,
+    /// R1-R15, one token each.
+    Cri,
+    Spectrum5nm,
+    Spectrum1nm,
+    /// Four text numbers.
+    Unk14,
+    /// Two floats.
+    Unk15,
+    Ppfd,
+}
+
+/// The order of the fields in a firmware's MR response. Anything after the
+/// last one ends up in the capture's undecoded remainder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// Version prefixes, as in [`FirmwareVersion::version`], this layout is
+    /// for. Empty for the default.
+    pub firmware: &'static [&'static str],
+    pub capture_info: &'static [MrField],
+}
+
+// every layout but the default
+const LAYOUTS: &[FieldLayout] = &[];
+
+impl FieldLayout {
+    /// The layout of every firmware seen so far, and the one used when the
+    /// firmware version can't be read.
+    pub const DEFAULT: FieldLayout = FieldLayout {
+        firmware: &[],
+        capture_info: &[
+            MrField::Unk0,
+            MrField::Title,
+            MrField::Unk1,
+            MrField::Unk2,
+            MrField::Unk3,
+            MrField::Unk4,
+            MrField::Unk5,
+            MrField::Unk6,
+            MrField::Unk7,
+            MrField::Unk8,
+            MrField::CctK,
+            MrField::Duv,
+            MrField::Unk11,
+            MrField::Unks,
+            MrField::IlluminanceLx,
+            MrField::IlluminanceFc,
+            MrField::TristimulusX,
+            MrField::TristimulusY,
+            MrField::TristimulusZ,
+            MrField::Cie1931X,
+            MrField::Cie1931Y,
+            MrField::Cie1976Up,
+            MrField::Unk12,
+            MrField::Unk13,
+            MrField::Cie1976Vp,
+            MrField::DominantWavelength,
+            MrField::Purity,
+            MrField::CriRa,
+            MrField::Cri,
+            MrField::Spectrum5nm,
+            MrField::Spectrum1nm,
+            MrField::Unk14,
+            MrField::Unk15,
+            MrField::Ppfd,
+        ],
+    };
+
+    /// The layout for `firmware`, [`FieldLayout::DEFAULT`] unless one of the
+    /// others claims it.
+    pub fn for_firmware(firmware: &FirmwareVersion) -> &'static FieldLayout {
+        LAYOUTS
+            .iter()
+            .find(|l| l.firmware.iter().any(|v| firmware.version.starts_with(v)))
+            .unwrap_or(&FieldLayout::DEFAULT)
+    }
+}
@@ -0,0 +1,3572 @@
+//! Talk to a Sekonic C-7000 spectrometer over USB.
+//!
+//! [`SekonicDevice::open`] claims the one meter that's plugged in. To pick one
+//! by serial number, or change the timeout or how often requests are retried,
+//! go through [`SekonicDevice::builder`]:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use sekonic_c_7000::SekonicDevice;
+//!
+//! # fn main() -> sekonic_c_7000::Result<()> {
+//! let ctx = rusb::Context::new()?;
+//! let mut dev = SekonicDevice::builder()
+//!     .serial("12345")
+//!     .timeout(Duration::from_secs(2))
+//!     .retries(5)
+//!     .build(&ctx)?;
+//! dev.start_session()?;
+//! println!("{} captures", dev.storage_info()?.num_captures);
+//! dev.end_session()?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "async")]
+mod async_device;
+mod cache;
+pub mod color;
+mod layout;
+mod mock;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+
+#[cfg(feature = "async")]
+pub use async_device::AsyncSekonicDevice;
+pub use cache::ScanCache;
+pub use layout::{FieldLayout, MrField};
+pub use mock::{MockTransport, RecordingTransport};
+#[cfg(feature = "sqlite")]
+pub use sqlite::write_sqlite;
+#[cfg(feature = "xlsx")]
+pub use xlsx::write_xlsx;
+
+use std::{
+    array,
+    cmp::min,
+    collections::BTreeMap,
+    ffi::OsString,
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    ops::{Deref, DerefMut},
+    path::Path,
+    str, thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, trace, warn};
+use pretty_hex::PrettyHex;
+use rusb::{Context, Device, DeviceHandle, Direction, TransferType, UsbContext};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub const VENDOR_ID: u16 = 0x0a41;
+/// The C-7000's, see [`DeviceModel`] for the other meters.
+pub const PRODUCT_ID: u16 = 0x7003;
+
+/// Which Sekonic meter a device on the bus is, going by its product id.
+///
+/// Only the C-7000 so far. Meters speaking the same protocol, like the
+/// C-800, can be added once their product id has been read off a real one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DeviceModel {
+    C7000,
+}
+
+impl DeviceModel {
+    pub const ALL: [DeviceModel; 1] = [DeviceModel::C7000];
+
+    /// Its product id under [`VENDOR_ID`].
+    pub const fn product_id(self) -> u16 {
+        match self {
+            DeviceModel::C7000 => PRODUCT_ID,
+        }
+    }
+
+    pub fn from_ids(vendor_id: u16, product_id: u16) -> Option<DeviceModel> {
+        if vendor_id != VENDOR_ID {
+            return None;
+        }
+        DeviceModel::ALL
+            .into_iter()
+            .find(|m| m.product_id() == product_id)
+    }
+}
+
+impl fmt::Display for DeviceModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DeviceModel::C7000 => "C-7000",
+        })
+    }
+}
+
+/// Per transfer USB timeout, unless changed with [`SekonicDevice::with_timeout`].
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1000);
+// upper bound on a whole response, which can span several bulk transfers, in
+// multiples of the per transfer timeout
+const RESPONSE_DEADLINE_TIMEOUTS: u32 = 10;
+
+/// How many times a request is tried, unless changed with
+/// [`SekonicDevice::with_max_attempts`].
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+// wait before the first retry, doubled each retry after that
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+const RESP_OK: [u8; 2] = [0x6, 0x30];
+const RESP_BADREQ: [u8; 2] = [0x15, 0x32];
+
+/// Bytes that print as a hex dump in `{:?}`, for the parts of responses that
+/// aren't decoded yet.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct HVec(pub Vec<u8>);
+
+impl Deref for HVec {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for HVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0.hex_dump())
+    }
+}
+
+impl From<Vec<u8>> for HVec {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+/// Everything talking to the meter can fail with.
+///
+/// The exporters (`write_*`) still return `anyhow::Result`, since all that
+/// goes wrong there is the file or the library writing it.
+#[derive(Debug)]
+pub enum SekonicError {
+    Usb(rusb::Error),
+    /// A response that doesn't look like what was asked for.
+    Parse {
+        context: String,
+    },
+    BadRequest,
+    UnexpectedResponse(Vec<u8>),
+    ShortRead {
+        expected: usize,
+        got: usize,
+    },
+    DeviceNotFound,
+    /// More than one meter is plugged in and nothing said which to use.
+    AmbiguousDevice(Vec<DeviceSummary>),
+    SerialNotFound {
+        serial: String,
+        found: Vec<DeviceSummary>,
+    },
+    NoBulkEndpoints {
+        bus: u8,
+        address: u8,
+    },
+    /// The payload was still coming in when the response deadline passed.
+    IncompleteResponse {
+        after: Duration,
+        got: usize,
+    },
+    /// Arguments out of range, like a title id of 0.
+    InvalidArgument(&'static str),
+    /// Something the meter can't do over USB, or at least that nobody has
+    /// found a request for yet.
+    Unsupported(&'static str),
+    /// The meter answered OK but sent no payload, which happens with
+    /// requests it accepts but doesn't act on. Has the request that got it.
+    EmptyResponse(String),
+    /// Reading or writing the response files of a [`MockTransport`] or
+    /// [`RecordingTransport`].
+    Io(std::io::Error),
+}
+
+impl SekonicError {
+    pub(crate) fn parse(context: impl Into<String>) -> SekonicError {
+        SekonicError::Parse {
+            context: context.into(),
+        }
+    }
+}
+
+impl fmt::Display for SekonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SekonicError::Usb(e) => write!(f, "{e}"),
+            SekonicError::Parse { context } => write!(f, "{context}"),
+            SekonicError::BadRequest => {
+                write!(f, "bad request (response {})", hex2(&RESP_BADREQ))
+            }
+            SekonicError::UnexpectedResponse(resp) => write!(f, "unknown response {}", hex2(resp)),
+            SekonicError::ShortRead { expected, got } => {
+                write!(f, "expected {expected} bytes from first bulk in, got {got}")
+            }
+            SekonicError::DeviceNotFound => write!(f, "no Sekonic meter detected"),
+            SekonicError::AmbiguousDevice(found) => write!(
+                f,
+                "found {} Sekonic meters ({}), not sure which one to use",
+                found.len(),
+                join(found)
+            ),
+            SekonicError::SerialNotFound { serial, found } => {
+                write!(f, "no Sekonic meter with serial {serial}, found {}", join(found))
+            }
+            SekonicError::NoBulkEndpoints { bus, address } => write!(
+                f,
+                "no interface with both a bulk IN and bulk OUT endpoint on bus {bus} address {address}"
+            ),
+            SekonicError::IncompleteResponse { after, got } => {
+                write!(f, "response not complete after {after:?}, got {got} bytes")
+            }
+            SekonicError::InvalidArgument(what) => write!(f, "{what}"),
+            SekonicError::Unsupported(what) => write!(f, "{what} is not supported over USB"),
+            SekonicError::EmptyResponse(req) => {
+                write!(f, "the meter answered {req:?} with an empty payload")
+            }
+            SekonicError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for SekonicError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SekonicError::Usb(e) => Some(e),
+            SekonicError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusb::Error> for SekonicError {
+    fn from(e: rusb::Error) -> Self {
+        SekonicError::Usb(e)
+    }
+}
+
+impl From<std::io::Error> for SekonicError {
+    fn from(e: std::io::Error) -> Self {
+        SekonicError::Io(e)
+    }
+}
+
+/// `Result` with [`SekonicError`] as the default error.
+pub type Result<T, E = SekonicError> = std::result::Result<T, E>;
+
+fn join(found: &[DeviceSummary]) -> String {
+    found
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// the first write after enumeration sometimes times out, and a retry goes through fine
+fn is_transient(e: &SekonicError) -> bool {
+    matches!(
+        e,
+        SekonicError::Usb(rusb::Error::Timeout | rusb::Error::Pipe)
+    )
+}
+
+// first two bytes of a response, which is where the status lives
+fn hex2(b: &[u8]) -> String {
+    hex_head(b, 2)
+}
+
+// without spaces, for keys and storage
+fn to_hex(b: &[u8]) -> String {
+    b.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hex_head(b: &[u8], n: usize) -> String {
+    b.iter()
+        .take(n)
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Carries a request to the meter and brings back the payload of the response.
+///
+/// [`UsbTransport`] is the real thing, [`MockTransport`] replays canned
+/// responses.
+pub trait Transport {
+    fn request(&mut self, req: &[u8]) -> Result<Vec<u8>>;
+
+    /// The USB serial number of the meter at the other end, if there is one
+    /// and it could be read.
+    fn serial(&self) -> Option<String> {
+        None
+    }
+
+    /// Give the meter back, like releasing the claimed USB interface. A
+    /// [`SekonicDevice`] calls this when it's closed or dropped, and nothing
+    /// is sent after it. Calling it again does nothing.
+    fn release(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn request(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        (**self).request(req)
+    }
+
+    fn serial(&self) -> Option<String> {
+        (**self).serial()
+    }
+
+    fn release(&mut self) -> Result<()> {
+        (**self).release()
+    }
+}
+
+/// The claimed bulk interface of a C-7000.
+///
+/// The interface is claimed once when it's opened and stays claimed for every
+/// request after that. It's released by [`Transport::release`], which the
+/// [`SekonicDevice`] it's in calls on close or drop, so a run that errors out
+/// doesn't leave the meter unusable until it's replugged. One used on its own
+/// releases on drop too.
+pub struct UsbTransport {
+    handle: DeviceHandle<Context>,
+    interface: u8,
+    // false once close() released it, so drop doesn't do it again
+    claimed: bool,
+    // a kernel driver had the interface before us and should get it back
+    reattach_kernel_driver: bool,
+    in_endpoint: u8,
+    out_endpoint: u8,
+    timeout: Duration,
+    max_attempts: u32,
+    model: DeviceModel,
+}
+
+impl Drop for UsbTransport {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            warn!("failed to release interface {}: {e}", self.interface);
+        }
+    }
+}
+
+impl Transport for UsbTransport {
+    fn request(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        self.make_req_retrying(req)
+    }
+
+    fn serial(&self) -> Option<String> {
+        let desc = self.handle.device().device_descriptor().ok()?;
+        desc.serial_number_string_index()?;
+        self.handle.read_serial_number_string_ascii(&desc).ok()
+    }
+
+    // and give it back to the kernel driver that had it
+    fn release(&mut self) -> Result<()> {
+        if !self.claimed {
+            return Ok(());
+        }
+        self.claimed = false;
+        let released = self.handle.release_interface(self.interface);
+        if self.reattach_kernel_driver {
+            if let Err(e) = self.handle.attach_kernel_driver(self.interface) {
+                warn!("failed to reattach the kernel driver: {e}");
+            }
+        }
+        Ok(released?)
+    }
+}
+
+impl UsbTransport {
+    /// Release the interface, reporting what went wrong instead of only
+    /// logging it like drop does.
+    pub fn close(mut self) -> Result<()> {
+        self.release()
+    }
+
+    pub fn model(&self) -> DeviceModel {
+        self.model
+    }
+
+    fn make_req_retrying(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            match self.make_req_once(req) {
+                Err(e) if attempt < self.max_attempts && is_transient(&e) => {
+                    debug!(
+                        "attempt {attempt} of {:?} failed, retrying: {e}",
+                        String::from_utf8_lossy(req)
+                    );
+                    thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    fn make_req_once(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        self.handle
+            .write_bulk(self.out_endpoint, req, self.timeout)?;
+
+        let mut buf = [0; 8192];
+        let len = self
+            .handle
+            .read_bulk(self.in_endpoint, &mut buf, self.timeout)?;
+
+        check_status(&buf[..len])?;
+        self.read_payload(&mut buf)
+    }
+
+    // The payload after RESP_OK can be split over several bulk transfers. libusb
+    // completes a transfer early on a short packet, so a transfer that fills the
+    // whole buffer means there is more to come.
+    fn read_payload(&mut self, buf: &mut [u8]) -> Result<Vec<u8>> {
+        let max_time = self.timeout * RESPONSE_DEADLINE_TIMEOUTS;
+        let deadline = Instant::now() + max_time;
+        let mut ret = Vec::new();
+        loop {
+            if Instant::now() > deadline {
+                return Err(SekonicError::IncompleteResponse {
+                    after: max_time,
+                    got: ret.len(),
+                });
+            }
+            let len = match self.handle.read_bulk(self.in_endpoint, buf, self.timeout) {
+                Ok(len) => len,
+                // payload was an exact multiple of the buffer and the device didn't
+                // bother with a zero length packet
+                Err(rusb::Error::Timeout) if !ret.is_empty() => break,
+                Err(e) => return Err(e.into()),
+            };
+            ret.extend_from_slice(&buf[..len]);
+            if len < buf.len() {
+                break;
+            }
+        }
+        Ok(ret)
+    }
+}
+
+// The first transfer of every response, RESP_OK if the payload follows
+pub(crate) fn check_status(frame: &[u8]) -> Result<()> {
+    match frame.try_into() {
+        Ok(RESP_OK) => Ok(()),
+        Ok(RESP_BADREQ) => Err(SekonicError::BadRequest),
+        _ if frame.len() < 2 => Err(SekonicError::ShortRead {
+            expected: 2,
+            got: frame.len(),
+        }),
+        _ => Err(SekonicError::UnexpectedResponse(frame.to_vec())),
+    }
+}
+
+/// A C-7000, or something pretending to be one.
+///
+/// [`SekonicDevice::open`] claims the meter, and every method after that
+/// reuses the claim, so one device can make any number of requests, like in a
+/// long running server. [`SekonicDevice::close`] or dropping it releases the
+/// meter.
+pub struct SekonicDevice<T: Transport = UsbTransport> {
+    transport: Releasing<T>,
+    layout: &'static FieldLayout,
+}
+
+// The transport of a SekonicDevice, released on drop unless into_inner took
+// it back out
+struct Releasing<T: Transport>(Option<T>);
+
+impl<T: Transport> Releasing<T> {
+    fn into_inner(mut self) -> T {
+        self.0.take().expect("only taken by into_inner")
+    }
+}
+
+impl<T: Transport> Deref for Releasing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.0.as_ref().expect("only taken by into_inner")
+    }
+}
+
+impl<T: Transport> DerefMut for Releasing<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.as_mut().expect("only taken by into_inner")
+    }
+}
+
+impl<T: Transport> Drop for Releasing<T> {
+    fn drop(&mut self) {
+        if let Some(Err(e)) = self.0.as_mut().map(T::release) {
+            warn!("failed to release the meter: {e}");
+        }
+    }
+}
+
+/// Options for opening a C-7000, from [`SekonicDevice::builder`]. Anything not
+/// set is the same as [`SekonicDevice::open`].
+#[derive(Debug, Clone)]
+pub struct SekonicDeviceBuilder {
+    serial: Option<String>,
+    timeout: Duration,
+    max_attempts: u32,
+}
+
+impl Default for SekonicDeviceBuilder {
+    fn default() -> SekonicDeviceBuilder {
+        SekonicDeviceBuilder {
+            serial: None,
+            timeout: DEFAULT_TIMEOUT,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+}
+
+impl SekonicDeviceBuilder {
+    /// Open the meter with this USB serial number, see
+    /// [`SekonicDevice::open_by_serial`]. Without one, there has to be exactly
+    /// one meter plugged in.
+    pub fn serial(mut self, serial: &str) -> SekonicDeviceBuilder {
+        self.serial = Some(serial.to_owned());
+        self
+    }
+
+    /// See [`SekonicDevice::with_timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> SekonicDeviceBuilder {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry a request that timed out or stalled up to `retries` times. The
+    /// default is [`DEFAULT_MAX_ATTEMPTS`] - 1, see
+    /// [`SekonicDevice::with_max_attempts`].
+    pub fn retries(mut self, retries: u32) -> SekonicDeviceBuilder {
+        self.max_attempts = retries.saturating_add(1);
+        self
+    }
+
+    pub fn build(self, ctx: &Context) -> Result<SekonicDevice> {
+        let dev = match &self.serial {
+            Some(serial) => SekonicDevice::open_by_serial(ctx, serial)?,
+            None => SekonicDevice::open(ctx)?,
+        };
+        Ok(dev
+            .with_timeout(self.timeout)
+            .with_max_attempts(self.max_attempts))
+    }
+}
+
+impl SekonicDevice {
+    pub fn builder() -> SekonicDeviceBuilder {
+        SekonicDeviceBuilder::default()
+    }
+
+    /// Find the attached meter, a C-7000 or any other [`DeviceModel`], and
+    /// claim its bulk interface.
+    ///
+    /// Errors if there's more than one meter plugged in rather than guessing,
+    /// use [`SekonicDevice::open_by_serial`] to pick one.
+    pub fn open(ctx: &Context) -> Result<SekonicDevice> {
+        let found = matching_devices(ctx)?;
+        match found.len() {
+            0 => Err(SekonicError::DeviceNotFound),
+            1 => Self::open_device(&found[0]),
+            _ => Err(SekonicError::AmbiguousDevice(summaries(&found))),
+        }
+    }
+
+    /// Open the meter with the given USB serial number.
+    pub fn open_by_serial(ctx: &Context, serial: &str) -> Result<SekonicDevice> {
+        let found = matching_devices(ctx)?;
+        for d in &found {
+            if read_serial(d).as_deref() == Some(serial) {
+                return Self::open_device(d);
+            }
+        }
+        if found.is_empty() {
+            return Err(SekonicError::DeviceNotFound);
+        }
+        Err(SekonicError::SerialNotFound {
+            serial: serial.to_owned(),
+            found: summaries(&found),
+        })
+    }
+
+    /// Every attached meter, without claiming any of them.
+    pub fn list(ctx: &Context) -> Result<Vec<DeviceSummary>> {
+        Ok(matching_devices(ctx)?
+            .iter()
+            .map(DeviceSummary::of)
+            .collect())
+    }
+
+    fn open_device(d: &Device<Context>) -> Result<SekonicDevice> {
+        let desc = d.device_descriptor()?;
+        let model = DeviceModel::from_ids(desc.vendor_id(), desc.product_id())
+            .ok_or(SekonicError::DeviceNotFound)?;
+        let ep = find_bulk_endpoints(d)?.ok_or(SekonicError::NoBulkEndpoints {
+            bus: d.bus_number(),
+            address: d.address(),
+        })?;
+
+        let handle = d.open()?;
+        // some distros bind a driver to it, which makes claiming fail with Busy
+        let reattach_kernel_driver = rusb::supports_detach_kernel_driver()
+            && handle.kernel_driver_active(ep.interface).unwrap_or(false);
+        if reattach_kernel_driver {
+            debug!("detaching kernel driver from interface {}", ep.interface);
+            handle.detach_kernel_driver(ep.interface)?;
+        }
+        let claimed = handle
+            .set_active_configuration(ep.config)
+            .and_then(|()| handle.claim_interface(ep.interface));
+        if let Err(e) = claimed {
+            if reattach_kernel_driver {
+                let _ = handle.attach_kernel_driver(ep.interface);
+            }
+            return Err(e.into());
+        }
+        // h.set_alternate_setting(interface_desc.interface_number(), interface_desc.setting_number()).unwrap();
+
+        Ok(SekonicDevice::new(UsbTransport {
+            handle,
+            interface: ep.interface,
+            claimed: true,
+            reattach_kernel_driver,
+            in_endpoint: ep.in_addr,
+            out_endpoint: ep.out_addr,
+            timeout: DEFAULT_TIMEOUT,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            model,
+        }))
+    }
+
+    /// Use `timeout` for each bulk transfer instead of [`DEFAULT_TIMEOUT`].
+    /// Handy on slow hubs, where the big capture responses can take a while.
+    pub fn with_timeout(mut self, timeout: Duration) -> SekonicDevice {
+        self.transport.timeout = timeout;
+        self
+    }
+
+    /// Try each request up to `max_attempts` times (at least once) when it
+    /// fails with a timeout or a stall. Anything else fails right away.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> SekonicDevice {
+        self.transport.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.transport.max_attempts
+    }
+
+    /// Which meter was opened.
+    pub fn model(&self) -> DeviceModel {
+        self.transport.model
+    }
+}
+
+impl<T: Transport> SekonicDevice<T> {
+    pub fn new(transport: T) -> SekonicDevice<T> {
+        SekonicDevice {
+            transport: Releasing(Some(transport)),
+            layout: &FieldLayout::DEFAULT,
+        }
+    }
+
+    pub fn transport(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// The transport, still holding the meter.
+    pub fn into_transport(self) -> T {
+        self.transport.into_inner()
+    }
+
+    /// Release the meter, see [`Transport::release`]. Dropping the device
+    /// does the same, this is for when the error matters or the release has
+    /// to happen at a known point.
+    pub fn close(self) -> Result<()> {
+        self.transport.into_inner().release()
+    }
+
+    /// How MR responses are parsed, picked from the firmware version by
+    /// [`SekonicDevice::start_session`]. [`FieldLayout::DEFAULT`] until then.
+    pub fn layout(&self) -> &'static FieldLayout {
+        self.layout
+    }
+
+    /// Parse MR responses with `layout` instead of what the firmware version
+    /// says, e.g. to try out a new layout.
+    pub fn set_layout(&mut self, layout: &'static FieldLayout) {
+        self.layout = layout;
+    }
+
+    // the dumps here are for when a new firmware breaks parsing
+    fn make_req(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        trace!("REQ: {:?}", String::from_utf8_lossy(req));
+        let resp = HVec(self.transport.request(req)?);
+        trace!("RESP: {resp:?}");
+        Ok(resp.0)
+    }
+
+    /// Send any request and get back the payload of the response as it is,
+    /// for trying out requests nobody has decoded yet. Some of those might
+    /// well change or delete things on the meter.
+    pub fn request(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        self.make_req(req)
+    }
+
+    // make_req for requests whose payload gets parsed, where an empty one
+    // would otherwise come out as a confusing prefix error
+    fn query(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        let resp = self.make_req(req)?;
+        if resp.is_empty() {
+            return Err(SekonicError::EmptyResponse(
+                String::from_utf8_lossy(req).into_owned(),
+            ));
+        }
+        Ok(resp)
+    }
+
+    /// The setup sequence the desktop software sends before anything else.
+    pub fn start_session(&mut self) -> Result<()> {
+        // not entirely sure what these do, but do them for consistency
+        self.make_req(b"ST")?;
+        self.make_req(b"RT0")?;
+        self.make_req(b"RT1")?;
+        self.make_req(b"MN")?;
+        self.make_req(b"SAr")?;
+        // the response isn't understood yet, but dump what there is of it
+        match FtSetting::parse(&self.make_req(b"FTr")?) {
+            Ok(ft) => debug!("FTr: {ft:?}"),
+            Err(e) => debug!("couldn't parse FTr: {e:#}"),
+        }
+        match FirmwareVersion::parse(&self.make_req(b"FV")?) {
+            Ok(fv) => {
+                self.layout = FieldLayout::for_firmware(&fv);
+                debug!("firmware {fv}, MR layout {:?}", self.layout.firmware);
+            }
+            Err(e) => debug!("couldn't parse FV, using the default MR layout: {e:#}"),
+        }
+        match IuInfo::parse(&self.make_req(b"IUr")?) {
+            Ok(iu) => debug!("IUr: {iu:?}"),
+            Err(e) => debug!("couldn't parse IUr: {e:#}"),
+        }
+        Ok(())
+    }
+
+    /// Take a new measurement, as if the measuring button was pressed, and
+    /// wait for it to be stored.
+    ///
+    /// No firmware is known to support this: the ST/RT0/RT1 requests in
+    /// [`SekonicDevice::start_session`] don't measure anything, and no other
+    /// request has been found that does. Until one is, this always fails with
+    /// [`SekonicError::Unsupported`], so callers can fall back to watching for
+    /// captures taken on the meter.
+    pub fn trigger_measurement(&mut self) -> Result<()> {
+        Err(SekonicError::Unsupported("remote triggering"))
+    }
+
+    /// Whether a measurement started by
+    /// [`SekonicDevice::trigger_measurement`] is still running. Fails with
+    /// [`SekonicError::Unsupported`] for the same reason.
+    pub fn measurement_status(&mut self) -> Result<MeasurementStatus> {
+        Err(SekonicError::Unsupported("measurement status"))
+    }
+
+    pub fn end_session(&mut self) -> Result<()> {
+        self.make_req(b"ST")?;
+        Ok(())
+    }
+
+    pub fn model_info(&mut self) -> Result<ModelInfo> {
+        ModelInfo::parse(&self.query(b"MN")?)
+    }
+
+    /// Something that tells meters apart. The serial number isn't decoded
+    /// yet, so this is the whole MN response it's presumably part of.
+    pub fn device_key(&mut self) -> Result<Vec<u8>> {
+        self.query(b"MN")
+    }
+
+    pub fn firmware_version(&mut self) -> Result<FirmwareVersion> {
+        FirmwareVersion::parse(&self.query(b"FV")?)
+    }
+
+    /// Serial, model and firmware, for [`write_all_json`]. Whatever can't be
+    /// read is left out rather than failing the export.
+    pub fn metadata(&mut self) -> DeviceMetadata {
+        DeviceMetadata {
+            serial: self.transport.serial(),
+            model: self.model_info().ok().map(|m| m.model),
+            firmware: self.firmware_version().ok().map(|fv| fv.version),
+        }
+    }
+
+    pub fn settings(&mut self) -> Result<Settings> {
+        Settings::parse(&self.query(b"SAr")?)
+    }
+
+    /// Whatever FTr reports, probably a filter or format setting. See
+    /// [`FtSetting`].
+    pub fn ft_setting(&mut self) -> Result<FtSetting> {
+        FtSetting::parse(&self.query(b"FTr")?)
+    }
+
+    /// Whatever IUr reports, probably units or instrument configuration. See
+    /// [`IuInfo`].
+    pub fn iu_info(&mut self) -> Result<IuInfo> {
+        IuInfo::parse(&self.query(b"IUr")?)
+    }
+
+    pub fn storage_info(&mut self) -> Result<StorageInfoResp> {
+        StorageInfoResp::parse(&self.query(b"MI")?)
+    }
+
+    // 1 indexed
+    pub fn title_info(&mut self, id: u32) -> Result<TitleInfo> {
+        check(id > 0, "title ids start at 1")?;
+        TitleInfo::parse(&self.query(format!("GT{id:04}").as_bytes())?)
+    }
+
+    /// Every title on the meter, in id order starting from 1.
+    pub fn titles(&mut self) -> Result<Vec<TitleInfo>> {
+        let num_titles = self.storage_info()?.num_titles;
+        (1..=num_titles).map(|id| self.title_info(id)).collect()
+    }
+
+    /// Check that the capture count in MI matches the sum of the per title
+    /// counts in GT, which a scan relies on. See
+    /// [`StorageInfoResp::check_titles`].
+    pub fn verify_counts(&mut self) -> Result<()> {
+        let info = self.storage_info()?;
+        let titles = (1..=info.num_titles)
+            .map(|id| self.title_info(id))
+            .collect::<Result<Vec<_>>>()?;
+        info.check_titles(&titles)
+    }
+
+    /// Rename title `id`.
+    ///
+    /// Only reads have been found in the protocol: GT and GA look titles up,
+    /// and RT0/RT1 are part of the session setup and don't change anything
+    /// that shows up in GT. So for now this fails with
+    /// [`SekonicError::Unsupported`], and titles have to be edited on the meter.
+    pub fn set_title_name(&mut self, _id: u32, _name: &str) -> Result<()> {
+        Err(SekonicError::Unsupported("renaming titles"))
+    }
+
+    /// Add a title named `name`. Unsupported for the same reason as
+    /// [`SekonicDevice::set_title_name`].
+    pub fn create_title(&mut self, _name: &str) -> Result<u32> {
+        Err(SekonicError::Unsupported("creating titles"))
+    }
+
+    // 1 indexed
+    pub fn global_capture_id(&mut self, title_id: u32, local_capture_id: u32) -> Result<u32> {
+        check(title_id > 0, "title ids start at 1")?;
+        check(
+            local_capture_id > 0,
+            "capture ids within a title start at 1",
+        )?;
+
+        parse_global_capture_id(
+            &self.query(format!("GA{title_id:04},{local_capture_id:04}").as_bytes())?,
+        )
+    }
+
+    pub fn capture_info(&mut self, global_capture_id: u32) -> Result<CaptureInfo> {
+        CaptureInfo::parse_with(&self.capture_info_raw(global_capture_id)?, self.layout)
+    }
+
+    /// The MR response behind [`SekonicDevice::capture_info`], unparsed.
+    pub fn capture_info_raw(&mut self, global_capture_id: u32) -> Result<Vec<u8>> {
+        self.query(format!("MR{global_capture_id:04}").as_bytes())
+    }
+
+    /// Remove a capture from the meter.
+    ///
+    /// No request for this is known, so it fails with
+    /// [`SekonicError::Unsupported`] and captures have to be deleted on the meter.
+    pub fn delete_capture(&mut self, _global_capture_id: u32) -> Result<()> {
+        Err(SekonicError::Unsupported("deleting captures"))
+    }
+
+    pub fn capture_data(&mut self, global_capture_id: u32) -> Result<CaptureData> {
+        CaptureData::parse(&self.query(format!("ME{global_capture_id:04}").as_bytes())?)
+    }
+
+    /// Both halves of capture `local_capture_id` in title `title_id`, looking
+    /// up its global id along the way. Both 1 indexed.
+    pub fn capture(&mut self, title_id: u32, local_capture_id: u32) -> Result<Capture> {
+        let global_capture_id = self.global_capture_id(title_id, local_capture_id)?;
+        Capture::fetch(self, global_capture_id, local_capture_id)
+    }
+}
+
+/// Which meter a set of captures came from, see [`SekonicDevice::metadata`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeviceMetadata {
+    pub serial: Option<String>,
+    pub model: Option<String>,
+    pub firmware: Option<String>,
+}
+
+/// A meter on the bus that hasn't been opened.
+#[derive(Debug, Clone)]
+pub struct DeviceSummary {
+    pub bus_number: u8,
+    pub address: u8,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    // None if the device has no serial or it couldn't be read (permissions, usually)
+    pub serial: Option<String>,
+}
+
+impl DeviceSummary {
+    /// None for ids that aren't a known model, which discovery never returns.
+    pub fn model(&self) -> Option<DeviceModel> {
+        DeviceModel::from_ids(self.vendor_id, self.product_id)
+    }
+
+    fn of(d: &Device<Context>) -> DeviceSummary {
+        // matching_devices already read the descriptor successfully
+        let desc = d.device_descriptor().unwrap();
+        DeviceSummary {
+            bus_number: d.bus_number(),
+            address: d.address(),
+            vendor_id: desc.vendor_id(),
+            product_id: desc.product_id(),
+            serial: read_serial(d),
+        }
+    }
+}
+
+impl fmt::Display for DeviceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(model) = self.model() {
+            write!(f, "{model} on ")?;
+        }
+        write!(
+            f,
+            "bus {} address {} ({:04x}:{:04x}) serial {}",
+            self.bus_number,
+            self.address,
+            self.vendor_id,
+            self.product_id,
+            self.serial.as_deref().unwrap_or("unknown")
+        )
+    }
+}
+
+fn matching_devices(ctx: &Context) -> rusb::Result<Vec<Device<Context>>> {
+    let mut found = vec![];
+    for d in ctx.devices()?.iter() {
+        let desc = d.device_descriptor()?;
+        if DeviceModel::from_ids(desc.vendor_id(), desc.product_id()).is_some() {
+            found.push(d);
+        }
+    }
+    Ok(found)
+}
+
+fn summaries(devs: &[Device<Context>]) -> Vec<DeviceSummary> {
+    devs.iter().map(DeviceSummary::of).collect()
+}
+
+// opens the device just long enough to read the string descriptor, doesn't claim anything
+fn read_serial(d: &Device<Context>) -> Option<String> {
+    let desc = d.device_descriptor().ok()?;
+    desc.serial_number_string_index()?;
+    d.open().ok()?.read_serial_number_string_ascii(&desc).ok()
+}
+
+struct BulkEndpoints {
+    config: u8,
+    interface: u8,
+    in_addr: u8,
+    out_addr: u8,
+}
+
+// first interface that has both a bulk IN and a bulk OUT endpoint
+fn find_bulk_endpoints(d: &Device<Context>) -> rusb::Result<Option<BulkEndpoints>> {
+    let desc = d.device_descriptor()?;
+    for n in 0..desc.num_configurations() {
+        // one unreadable configuration shouldn't hide the others, but say so
+        // in case that's why NoBulkEndpoints comes up
+        let config_desc = match d.config_descriptor(n) {
+            Ok(c) => c,
+            Err(e) => {
+                debug!("couldn't read configuration descriptor {n}: {e}");
+                continue;
+            }
+        };
+
+        for interface in config_desc.interfaces() {
+            for interface_desc in interface.descriptors() {
+                let mut out_endpoint = None;
+                let mut in_endpoint = None;
+                for endpoint_desc in interface_desc.endpoint_descriptors() {
+                    if endpoint_desc.transfer_type() != TransferType::Bulk {
+                        continue;
+                    }
+                    let dir = match endpoint_desc.direction() {
+                        Direction::Out => {
+                            out_endpoint = Some(endpoint_desc.address());
+                            "OUT"
+                        }
+                        Direction::In => {
+                            in_endpoint = Some(endpoint_desc.address());
+                            "IN"
+                        }
+                    };
+                    debug!(
+                        "found {} endpoint number={} config={} iface={} setting={} address={}",
+                        dir,
+                        endpoint_desc.number(),
+                        config_desc.number(),
+                        interface_desc.interface_number(),
+                        interface_desc.setting_number(),
+                        endpoint_desc.address()
+                    );
+                }
+                if let (Some(out_addr), Some(in_addr)) = (out_endpoint, in_endpoint) {
+                    return Ok(Some(BulkEndpoints {
+                        config: config_desc.number(),
+                        interface: interface_desc.interface_number(),
+                        in_addr,
+                        out_addr,
+                    }));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn check(ok: bool, what: &'static str) -> Result<()> {
+    if ok {
+        Ok(())
+    } else {
+        Err(SekonicError::InvalidArgument(what))
+    }
+}
+
+// A float sent as decimal text instead of 4 big-endian bytes. No field is
+// known to be sent like that, remaining_report tries it on what's undecoded
+fn parse_ascii_float(b: &[u8]) -> Option<f32> {
+    str::from_utf8(b).ok()?.trim().parse().ok()
+}
+
+// "GAB" structure
+fn parse_global_capture_id(i: &[u8]) -> Result<u32> {
+    ParseHelper::start(i, "GAB")?
+        .unsigned()
+        .ok_or_else(|| SekonicError::parse("GA response has no capture id"))
+}
+
+struct ParseHelper<'a> {
+    remaining: &'a [u8],
+    // between tokens, `,` in everything seen so far
+    separator: u8,
+}
+
+impl<'a> ParseHelper<'a> {
+    fn start(to_parse: &'a [u8], name: &str) -> Result<ParseHelper<'a>> {
+        ParseHelper::start_with(to_parse, name, b',')
+    }
+
+    // for a response whose prefix isn't known: all of it is split into tokens,
+    // whatever it starts with
+    fn unprefixed(to_parse: &'a [u8]) -> ParseHelper<'a> {
+        ParseHelper {
+            remaining: to_parse,
+            separator: b',',
+        }
+    }
+
+    // start() for a response that splits its tokens with something other
+    // than `,`, which no known one does
+    fn start_with(to_parse: &'a [u8], name: &str, separator: u8) -> Result<ParseHelper<'a>> {
+        if !to_parse.starts_with(name.as_bytes()) {
+            return Err(SekonicError::parse(format!(
+                "expected prefix {name:?} but got {}",
+                hex_head(to_parse, name.len() + 2)
+            )));
+        }
+
+        // both of these seem to happen. idk if there's rhyme or reason to it
+        let Some(next2) = to_parse.get(name.len()..name.len() + 2) else {
+            return Err(SekonicError::parse(format!(
+                "response too short for {name:?} and a separator: {}",
+                hex_head(to_parse, name.len() + 2)
+            )));
+        };
+        if next2 != &b"@@"[..] && next2 != [0x40, 0x20] {
+            return Err(SekonicError::parse(format!(
+                "expected \"@@\" or \"@ \" at byte {} after {name:?} but got {}",
+                name.len(),
+                hex_head(next2, 2)
+            )));
+        }
+
+        Ok(ParseHelper {
+            remaining: &to_parse[name.len() + 2..],
+            separator,
+        })
+    }
+
+    fn bytes(&mut self) -> &'a [u8] {
+        let len = self
+            .remaining
+            .iter()
+            .position(|b| *b == self.separator)
+            .unwrap_or(self.remaining.len());
+        let ret = &self.remaining[..len];
+        self.remaining = &self.remaining[min(self.remaining.len(), len + 1)..];
+        ret
+    }
+
+    fn bytes_exact(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.remaining.len()
+            || (len < self.remaining.len() && self.remaining[len] != self.separator)
+        {
+            return Err(SekonicError::parse(format!(
+                "did not find a {:?} in the right distance",
+                char::from(self.separator)
+            )));
+        }
+        let ret = &self.remaining[..len];
+        self.remaining = &self.remaining[min(self.remaining.len(), len + 1)..];
+        Ok(ret)
+    }
+
+    fn unsigned(&mut self) -> Option<u32> {
+        str::from_utf8(self.bytes()).ok()?.parse().ok()
+    }
+
+    // unsigned() for fields that have to be there, naming `what` if it isn't
+    fn required_unsigned(&mut self, what: &str) -> Result<u32> {
+        self.unsigned()
+            .ok_or_else(|| SekonicError::parse(format!("{what} is missing or not a number")))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let str = str::from_utf8(self.bytes()).ok()?;
+        Some(
+            if let Some(idx) = str.find('\0') {
+                &str[..idx]
+            } else {
+                str
+            }
+            .to_owned(),
+        )
+    }
+
+    fn float(&mut self) -> Result<f32> {
+        let b = self.bytes_exact(4)?;
+        Ok(f32::from_be_bytes(b.try_into().map_err(|_| {
+            SekonicError::parse(format!("wrong length, expected 4 got {}", b.len()))
+        })?))
+    }
+
+    fn double(&mut self) -> Result<f64> {
+        let b = self.bytes_exact(8)?;
+        Ok(f64::from_be_bytes(b.try_into().map_err(|_| {
+            SekonicError::parse(format!("wrong length, expected 8 got {}", b.len()))
+        })?))
+    }
+
+    /// Bytes not consumed yet, separators included.
+    fn remaining_len(&self) -> usize {
+        self.remaining.len()
+    }
+
+    fn collect_remaining(&mut self) -> Vec<HVec> {
+        trace!("collecting {} remaining bytes", self.remaining_len());
+        let mut ret = vec![];
+        loop {
+            let b = self.bytes();
+            if b.is_empty() {
+                return ret;
+            }
+
+            ret.push(b.to_owned().into())
+        }
+    }
+
+    fn float_array<const LEN: usize>(&mut self) -> Result<[f32; LEN]> {
+        let b = self.bytes_exact(4 * LEN)?;
+        // bytes_exact already checks this, don't rely on it for the indexing below
+        if b.len() < 4 * LEN {
+            return Err(SekonicError::parse(format!(
+                "wrong length, expected {} got {}",
+                4 * LEN,
+                b.len()
+            )));
+        }
+        Ok(array::from_fn(|i| {
+            f32::from_be_bytes([b[i * 4], b[i * 4 + 1], b[i * 4 + 2], b[i * 4 + 3]])
+        }))
+    }
+}
+
+// "MNB" structure
+#[derive(Debug)]
+pub struct ModelInfo {
+    pub model: String,
+    // identification fields after the name, not decoded yet
+    remaining: Vec<HVec>,
+}
+
+impl ModelInfo {
+    pub fn parse(i: &[u8]) -> Result<ModelInfo> {
+        let mut p = ParseHelper::start(i, "MNB")?;
+        Ok(ModelInfo {
+            model: p
+                .string()
+                .ok_or_else(|| SekonicError::parse("model name is not utf8"))?,
+            remaining: p.collect_remaining(),
+        })
+    }
+
+    /// The fields after the model name that are printable text, in order.
+    pub fn text_fields(&self) -> Vec<&str> {
+        text_fields(&self.remaining)
+    }
+}
+
+// "FVB" structure
+#[derive(Debug)]
+pub struct FirmwareVersion {
+    pub version: String,
+    remaining: Vec<HVec>,
+}
+
+impl FirmwareVersion {
+    pub fn parse(i: &[u8]) -> Result<FirmwareVersion> {
+        let mut p = ParseHelper::start(i, "FVB")?;
+        Ok(FirmwareVersion {
+            version: p
+                .string()
+                .ok_or_else(|| SekonicError::parse("firmware version is not utf8"))?,
+            remaining: p.collect_remaining(),
+        })
+    }
+
+    /// The fields after the version that are printable text, in order.
+    pub fn text_fields(&self) -> Vec<&str> {
+        text_fields(&self.remaining)
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.version)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeasurementStatus {
+    Measuring,
+    Done,
+}
+
+/// The response to SAr.
+///
+/// Where the measuring mode and viewing angle live in here hasn't been worked
+/// out yet, so until then they're always the defaults, whatever the meter is
+/// set to. Like [`FtSetting`] not even the prefix is known ("SAB" would be a
+/// guess), so the whole payload is kept as is in [`Settings::raw`] for
+/// working it out.
+#[derive(Debug, Default)]
+pub struct Settings {
+    pub measuring_mode: MeasuringMode,
+    pub viewing_angle: ViewingAngle,
+    raw: HVec,
+}
+
+impl Settings {
+    /// Never fails until something in the response is decoded.
+    pub fn parse(i: &[u8]) -> Result<Settings> {
+        Ok(Settings {
+            raw: i.to_owned().into(),
+            ..Default::default()
+        })
+    }
+
+    /// The whole payload, none of it decoded.
+    pub fn raw(&self) -> &[u8] {
+        &self.raw.0
+    }
+
+    /// The observer to recompute colorimetry with so it matches what the
+    /// meter reports at this viewing angle.
+    pub fn observer(&self) -> color::Observer {
+        self.viewing_angle.into()
+    }
+}
+
+// FTr response
+//
+// No payload has been recorded yet, so not even the prefix is known. "FTB"
+// would go by the naming of the other responses, but that's a guess, so
+// nothing is checked and the whole payload, prefix and all, is kept in
+// `remaining` for the Debug output.
+#[derive(Debug)]
+pub struct FtSetting {
+    remaining: Vec<HVec>,
+}
+
+impl FtSetting {
+    /// Never fails until something in the response is decoded.
+    pub fn parse(i: &[u8]) -> Result<FtSetting> {
+        let mut p = ParseHelper::unprefixed(i);
+        Ok(FtSetting {
+            remaining: p.collect_remaining(),
+        })
+    }
+
+    /// The fields that are printable text, in order.
+    pub fn text_fields(&self) -> Vec<&str> {
+        text_fields(&self.remaining)
+    }
+}
+
+// IUr response
+//
+// Same story as FtSetting: the prefix isn't known and nothing is decoded yet.
+#[derive(Debug)]
+pub struct IuInfo {
+    remaining: Vec<HVec>,
+}
+
+impl IuInfo {
+    /// Never fails until something in the response is decoded.
+    pub fn parse(i: &[u8]) -> Result<IuInfo> {
+        let mut p = ParseHelper::unprefixed(i);
+        Ok(IuInfo {
+            remaining: p.collect_remaining(),
+        })
+    }
+
+    /// The fields that are printable text, in order.
+    pub fn text_fields(&self) -> Vec<&str> {
+        text_fields(&self.remaining)
+    }
+}
+
+fn text_fields(chunks: &[HVec]) -> Vec<&str> {
+    chunks
+        .iter()
+        .filter_map(|chunk| str::from_utf8(&chunk.0).ok())
+        .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_graphic() || c == ' '))
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MeasuringMode {
+    #[default]
+    Ambient,
+    CordlessFlash,
+    CordFlash,
+}
+
+impl fmt::Display for MeasuringMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MeasuringMode::Ambient => "Ambient",
+            MeasuringMode::CordlessFlash => "Cordless Flash",
+            MeasuringMode::CordFlash => "Cord Flash",
+        })
+    }
+}
+
+/// Standard observer the meter computes colorimetry for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ViewingAngle {
+    #[default]
+    TwoDegree,
+    TenDegree,
+}
+
+impl ViewingAngle {
+    pub fn degrees(self) -> u32 {
+        match self {
+            ViewingAngle::TwoDegree => 2,
+            ViewingAngle::TenDegree => 10,
+        }
+    }
+
+    pub fn from_degrees(degrees: u32) -> Option<ViewingAngle> {
+        match degrees {
+            2 => Some(ViewingAngle::TwoDegree),
+            10 => Some(ViewingAngle::TenDegree),
+            _ => None,
+        }
+    }
+}
+
+// "MIB" structure
+#[derive(Debug)]
+pub struct StorageInfoResp {
+    _unk1: u32,
+    pub num_captures: u32,
+    pub num_titles: u32,
+}
+
+impl StorageInfoResp {
+    pub fn parse(i: &[u8]) -> Result<StorageInfoResp> {
+        let mut p = ParseHelper::start(i, "MIB")?;
+        let mut count = |what| {
+            p.unsigned()
+                .ok_or_else(|| SekonicError::parse(format!("MI response has no {what}")))
+        };
+        Ok(StorageInfoResp {
+            _unk1: count("first field")?,
+            num_captures: count("capture count")?,
+            num_titles: count("title count")?,
+        })
+    }
+
+    /// Errors if `titles` don't add up to `num_captures`, or there aren't
+    /// `num_titles` of them. Either way something was misparsed or the meter
+    /// is in an odd state, and a scan would come up short or run over.
+    pub fn check_titles(&self, titles: &[TitleInfo]) -> Result<()> {
+        if titles.len() != self.num_titles as usize {
+            return Err(SekonicError::parse(format!(
+                "meter reports {} titles but {} were given",
+                self.num_titles,
+                titles.len()
+            )));
+        }
+        let sum: u32 = titles.iter().map(|t| t.num_captures).sum();
+        if sum != self.num_captures {
+            return Err(SekonicError::parse(format!(
+                "meter reports {} captures but its titles add up to {sum}",
+                self.num_captures
+            )));
+        }
+        Ok(())
+    }
+}
+
+// "GTB" structure
+#[derive(Debug)]
+pub struct TitleInfo {
+    pub name: String,
+    pub num_captures: u32,
+}
+
+impl TitleInfo {
+    pub fn parse(i: &[u8]) -> Result<TitleInfo> {
+        let mut p = ParseHelper::start(i, "GTB")?;
+        Ok(TitleInfo {
+            name: p
+                .string()
+                .ok_or_else(|| SekonicError::parse("GT title name is not utf8"))?,
+            num_captures: p.required_unsigned("GT capture count")?,
+        })
+    }
+}
+
+// "MRB" structure
+/// One capture as the MR response has it.
+///
+/// The `unk*` and `remaining` fields are the parts that aren't decoded yet,
+/// public so they can be looked at while working out what they are. They
+/// aren't stable: any of them can be renamed, change type or go away once
+/// it's known what it is, without that counting as a breaking change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureInfo {
+    #[serde(skip)]
+    pub unk0: u32,
+    pub title: String, // NOTE: not title of capture, title of "title", lol
+    #[serde(skip)]
+    pub unk1: u32, // 6
+    #[serde(skip)]
+    pub unk2: u32, // 0
+    #[serde(skip)]
+    pub unk3: u32, // 00
+    #[serde(skip)]
+    pub unk4: u32, // 0
+    #[serde(skip)]
+    pub unk5: HVec, // all null
+    #[serde(skip)]
+    pub unk6: u32, // 0
+    #[serde(skip)]
+    pub unk7: HVec, // all null
+    #[serde(skip)]
+    pub unk8: u32, // 0
+    pub cct_k: f32,
+    pub uv_angle: f32, // unsure what to call this lol. output has "⊿uv"
+    #[serde(skip)]
+    pub unk11: u32, // 0
+    #[serde(skip)]
+    pub unks: [HVec; 6],
+    pub illum_lx: f32,
+    pub illum_fc: f32,
+    pub tristimulus_x: f64,
+    pub tristimulus_y: f64,
+    pub tristimulus_z: f64,
+    pub cie1931_x: f32,
+    pub cie1931_y: f32,
+    // no z in the response, see cie1931_z()
+    pub cie1976_up: f32,
+    #[serde(skip)]
+    pub unk12: f32,
+    #[serde(skip)]
+    pub unk13: f32,
+    pub cie1976_vp: f32,
+    pub dominant_wavelength: f32,
+    pub purity: f32,
+    // ppfd: f32,
+    pub cri_ra: f32,
+    pub cri: [f32; 15],
+
+    // 5nm steps starting at 380nm
+    #[serde(
+        serialize_with = "serialize_array",
+        deserialize_with = "deserialize_array"
+    )]
+    pub spectral_data_5nm: [f32; 81],
+
+    // 1nm steps starting at 380nm
+    #[serde(
+        serialize_with = "serialize_array",
+        deserialize_with = "deserialize_array"
+    )]
+    pub spectral_data_1nm: [f32; 401],
+    #[serde(skip)]
+    pub unk14: [u32; 4],
+    #[serde(skip)]
+    pub unk15: [f32; 2],
+    pub ppfd: f32,
+
+    // tm_30_rf, tm_30_rg, ssit, ssid and tlci turned out to live in the ME
+    // response (CaptureData). SSI1, SSI2, TLMF and so many more are still
+    // unaccounted for and are probably somewhere in here, see
+    // remaining_report()
+    #[serde(skip)]
+    pub remaining: Vec<HVec>,
+}
+
+impl CaptureInfo {
+    // Everything read with float() and double() has to be exactly 4 or 8 bytes
+    // before the next ',', so a number sent as text would almost always fail
+    // loudly rather than come out as garbage. The text numbers are the
+    // unsigned() ones.
+    pub fn parse(i: &[u8]) -> Result<CaptureInfo> {
+        CaptureInfo::parse_with(i, &FieldLayout::DEFAULT)
+    }
+
+    /// Parse a response from firmware that orders the fields like `layout`.
+    pub fn parse_with(i: &[u8], layout: &FieldLayout) -> Result<CaptureInfo> {
+        let mut p = ParseHelper::start(i, "MRB")?;
+        let mut ret = CaptureInfo::empty();
+        for &field in layout.capture_info {
+            let wrong = |e: SekonicError| SekonicError::parse(format!("MR {field:?}: {e}"));
+            let missing = || SekonicError::parse(format!("MR {field:?} is missing or not text"));
+            match field {
+                MrField::Unk0 => ret.unk0 = p.unsigned().ok_or_else(missing)?,
+                MrField::Title => ret.title = p.string().ok_or_else(missing)?,
+                MrField::Unk1 => ret.unk1 = p.unsigned().ok_or_else(missing)?,
+                MrField::Unk2 => ret.unk2 = p.unsigned().ok_or_else(missing)?,
+                MrField::Unk3 => ret.unk3 = p.unsigned().ok_or_else(missing)?,
+                MrField::Unk4 => ret.unk4 = p.unsigned().ok_or_else(missing)?,
+                MrField::Unk5 => ret.unk5 = p.bytes().to_owned().into(),
+                MrField::Unk6 => ret.unk6 = p.unsigned().ok_or_else(missing)?,
+                MrField::Unk7 => ret.unk7 = p.bytes().to_owned().into(),
+                MrField::Unk8 => ret.unk8 = p.unsigned().ok_or_else(missing)?,
+                MrField::CctK => ret.cct_k = p.float().map_err(wrong)?,
+                MrField::Duv => ret.uv_angle = p.float().map_err(wrong)?,
+                MrField::Unk11 => ret.unk11 = p.unsigned().ok_or_else(missing)?,
+                MrField::Unks => ret.unks = array::from_fn(|_| p.bytes().to_owned().into()),
+                MrField::IlluminanceLx => ret.illum_lx = p.float().map_err(wrong)?,
+                MrField::IlluminanceFc => ret.illum_fc = p.float().map_err(wrong)?,
+                MrField::TristimulusX => ret.tristimulus_x = p.double().map_err(wrong)?,
+                MrField::TristimulusY => ret.tristimulus_y = p.double().map_err(wrong)?,
+                MrField::TristimulusZ => ret.tristimulus_z = p.double().map_err(wrong)?,
+                MrField::Cie1931X => ret.cie1931_x = p.float().map_err(wrong)?,
+                MrField::Cie1931Y => ret.cie1931_y = p.float().map_err(wrong)?,
+                MrField::Cie1976Up => ret.cie1976_up = p.float().map_err(wrong)?,
+                MrField::Unk12 => ret.unk12 = p.float().map_err(wrong)?,
+                MrField::Unk13 => ret.unk13 = p.float().map_err(wrong)?,
+                MrField::Cie1976Vp => ret.cie1976_vp = p.float().map_err(wrong)?,
+                MrField::DominantWavelength => {
+                    ret.dominant_wavelength = p.float().map_err(wrong)?
+                }
+                MrField::Purity => ret.purity = p.float().map_err(wrong)?,
+                MrField::CriRa => ret.cri_ra = p.float().map_err(wrong)?,
+                MrField::Cri => {
+                    for r in &mut ret.cri {
+                        *r = p.float().map_err(wrong)?;
+                    }
+                }
+                MrField::Spectrum5nm => ret.spectral_data_5nm = p.float_array().map_err(wrong)?,
+                MrField::Spectrum1nm => ret.spectral_data_1nm = p.float_array().map_err(wrong)?,
+                MrField::Unk14 => {
+                    for u in &mut ret.unk14 {
+                        *u = p.unsigned().ok_or_else(missing)?;
+                    }
+                }
+                MrField::Unk15 => {
+                    for u in &mut ret.unk15 {
+                        *u = p.float().map_err(wrong)?;
+                    }
+                }
+                MrField::Ppfd => ret.ppfd = p.float().map_err(wrong)?,
+            }
+        }
+        // what's left after the last known field, the quickest way to tell if
+        // a layout fits a firmware
+        debug!("{} bytes unparsed in remaining", p.remaining_len());
+        ret.remaining = p.collect_remaining();
+        if !ret.illuminance_agrees() {
+            warn!(
+                "capture \"{}\" has {} lx but {} fc, expected {} fc",
+                ret.title,
+                ret.illum_lx,
+                ret.illum_fc,
+                lux_to_fc(ret.illum_lx)
+            );
+        }
+        Ok(ret)
+    }
+
+    // everything zero, for parse_with to fill in
+    fn empty() -> CaptureInfo {
+        CaptureInfo {
+            unk0: 0,
+            title: String::new(),
+            unk1: 0,
+            unk2: 0,
+            unk3: 0,
+            unk4: 0,
+            unk5: HVec::default(),
+            unk6: 0,
+            unk7: HVec::default(),
+            unk8: 0,
+            cct_k: 0.,
+            uv_angle: 0.,
+            unk11: 0,
+            unks: Default::default(),
+            illum_lx: 0.,
+            illum_fc: 0.,
+            tristimulus_x: 0.,
+            tristimulus_y: 0.,
+            tristimulus_z: 0.,
+            cie1931_x: 0.,
+            cie1931_y: 0.,
+            cie1976_up: 0.,
+            unk12: 0.,
+            unk13: 0.,
+            cie1976_vp: 0.,
+            dominant_wavelength: 0.,
+            purity: 0.,
+            cri_ra: 0.,
+            cri: [0.; 15],
+            spectral_data_5nm: [0.; 81],
+            spectral_data_1nm: [0.; 401],
+            unk14: [0; 4],
+            unk15: [0.; 2],
+            ppfd: 0.,
+            remaining: Vec::new(),
+        }
+    }
+
+    pub fn spectrum_5nm(&self) -> Spectrum {
+        Spectrum {
+            start_nm: 380,
+            step_nm: 5,
+            values: self.spectral_data_5nm.to_vec(),
+        }
+    }
+
+    pub fn spectrum_1nm(&self) -> Spectrum {
+        Spectrum {
+            start_nm: 380,
+            step_nm: 1,
+            values: self.spectral_data_1nm.to_vec(),
+        }
+    }
+
+    /// Illuminance in lux. Parsing warns, once per capture, if it doesn't
+    /// agree with the foot-candle reading, which likely means the parser is
+    /// reading the wrong offsets.
+    pub fn illuminance_lux(&self) -> f32 {
+        self.illum_lx
+    }
+
+    /// Illuminance in foot-candles, see [`CaptureInfo::illuminance_lux`].
+    pub fn illuminance_fc(&self) -> f32 {
+        self.illum_fc
+    }
+
+    // whether the lx and fc readings are the same illuminance
+    fn illuminance_agrees(&self) -> bool {
+        let expected = lux_to_fc(self.illum_lx);
+        // the meter rounds, so only complain about real disagreement
+        (self.illum_fc - expected).abs() <= 0.01 * expected.abs() + 0.1
+    }
+
+    /// Whether the capture was taken in the dark, like with the cap on.
+    /// Below [`DARK_LUX`] the spectrum is noise, so CCT, chromaticity, the
+    /// rendering indices and everything else derived from its shape mean
+    /// nothing.
+    pub fn is_dark(&self) -> bool {
+        self.illum_lx < DARK_LUX
+    }
+
+    /// Distance from the Planckian locus, the ⊿uv of Sekonic's export.
+    /// Positive is above the locus, towards green.
+    pub fn duv(&self) -> f32 {
+        self.uv_angle
+    }
+
+    /// Which side of the Planckian locus the light is on, for people who
+    /// don't read Duv: "above locus (green)", "below locus (magenta)", or
+    /// "on locus" within [`DUV_ON_LOCUS`] of it. "unknown" if it's NaN.
+    pub fn duv_description(&self) -> &'static str {
+        let duv = self.duv();
+        if duv.is_nan() {
+            "unknown"
+        } else if duv > DUV_ON_LOCUS {
+            "above locus (green)"
+        } else if duv < -DUV_ON_LOCUS {
+            "below locus (magenta)"
+        } else {
+            "on locus"
+        }
+    }
+
+    /// Approximate sRGB color of the light for a preview swatch, scaled to the
+    /// luminance of sRGB white. `None` for dark captures, which have no color.
+    pub fn srgb(&self) -> Option<[u8; 3]> {
+        if self.is_dark() {
+            return None;
+        }
+        Some(
+            color::Xyz {
+                x: self.tristimulus_x / self.tristimulus_y,
+                y: 1.,
+                z: self.tristimulus_z / self.tristimulus_y,
+            }
+            .to_srgb(),
+        )
+    }
+
+    /// CIE 1960 UCS `(u, v)`, which the meter doesn't send but is just u' and
+    /// 2/3 v'.
+    pub fn cie1960_uv(&self) -> (f32, f32) {
+        (self.cie1976_up, self.cie1976_vp * 2. / 3.)
+    }
+
+    /// Chromaticity z. It doesn't seem to be in the response, and it's just 1 - x - y anyway.
+    pub fn cie1931_z(&self) -> f32 {
+        1. - self.cie1931_x - self.cie1931_y
+    }
+
+    /// The undecoded tail of the response, one labeled entry per chunk along
+    /// with the ways it could plausibly be read. For continuing to decode the
+    /// layout.
+    ///
+    /// Chunks are split on `,`, so a binary value that happens to contain 0x2c
+    /// shows up as two chunks.
+    pub fn remaining_report(&self) -> String {
+        use std::fmt::Write as _;
+
+        let mut ret = String::new();
+        for (i, chunk) in self.remaining.iter().enumerate() {
+            let b = &chunk.0;
+            let _ = writeln!(ret, "remaining[{i}]: {} bytes", b.len());
+            if let Ok(s) = str::from_utf8(b) {
+                if s.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+                    let _ = writeln!(ret, "  as text: {s:?}");
+                    if let Some(f) = parse_ascii_float(b) {
+                        let _ = writeln!(ret, "  as decimal: {f}");
+                    }
+                }
+            }
+            if b.len() % 4 == 0 {
+                let floats: Vec<_> = b
+                    .chunks_exact(4)
+                    .map(|f| f32::from_be_bytes([f[0], f[1], f[2], f[3]]))
+                    .collect();
+                let _ = writeln!(ret, "  as f32: {floats:?}");
+            }
+            if b.len() == 8 {
+                let _ = writeln!(
+                    ret,
+                    "  as f64: {}",
+                    f64::from_be_bytes(b[..].try_into().unwrap())
+                );
+            }
+            let _ = writeln!(ret, "{chunk:?}");
+        }
+        ret
+    }
+}
+
+// serde only does arrays up to 32 long
+fn serialize_array<S: Serializer, const N: usize>(v: &[f32; N], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(v)
+}
+
+fn deserialize_array<'de, D: Deserializer<'de>, const N: usize>(
+    d: D,
+) -> Result<[f32; N], D::Error> {
+    let v = Vec::<f32>::deserialize(d)?;
+    let len = v.len();
+    v.try_into()
+        .map_err(|_| serde::de::Error::invalid_length(len, &format!("{N} values").as_str()))
+}
+
+pub const LUX_PER_FOOT_CANDLE: f32 = 10.763_91;
+
+/// Illuminance below which [`CaptureInfo::is_dark`] considers a capture dark,
+/// the bottom of the C-7000's ambient measuring range.
+pub const DARK_LUX: f32 = 1.;
+
+/// How far from the Planckian locus [`CaptureInfo::duv_description`] still
+/// calls on it. ANSI C78.377 allows ±0.006 around a nominal CCT, so this is
+/// well inside what counts as white.
+pub const DUV_ON_LOCUS: f32 = 0.001;
+
+pub fn lux_to_fc(lx: f32) -> f32 {
+    lx / LUX_PER_FOOT_CANDLE
+}
+
+pub fn fc_to_lux(fc: f32) -> f32 {
+    fc * LUX_PER_FOOT_CANDLE
+}
+
+/// Spectral data on an evenly spaced wavelength axis.
+///
+/// [`Spectrum::new`] checks the axis makes sense. One put together from the
+/// fields directly with a `step_nm` of 0 has no points, and values past
+/// 65535nm are left off. Deserializing goes through [`Spectrum::new`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "SpectrumFields")]
+pub struct Spectrum {
+    pub start_nm: u16,
+    pub step_nm: u16,
+    pub values: Vec<f32>,
+}
+
+// what a Spectrum deserializes from before it's checked
+#[derive(Deserialize)]
+struct SpectrumFields {
+    start_nm: u16,
+    step_nm: u16,
+    values: Vec<f32>,
+}
+
+impl TryFrom<SpectrumFields> for Spectrum {
+    type Error = SekonicError;
+
+    fn try_from(f: SpectrumFields) -> Result<Spectrum> {
+        Spectrum::new(f.start_nm, f.step_nm, f.values)
+    }
+}
+
+impl Spectrum {
+    /// `values` at `start_nm` and every `step_nm` after it. Fails if
+    /// `step_nm` is 0 or the last value would be past 65535nm.
+    pub fn new(start_nm: u16, step_nm: u16, values: Vec<f32>) -> Result<Spectrum> {
+        check(step_nm > 0, "spectrum step must be at least 1nm")?;
+        let last_nm =
+            values.len().saturating_sub(1) as u64 * u64::from(step_nm) + u64::from(start_nm);
+        check(last_nm <= u64::from(u16::MAX), "spectrum runs past 65535nm")?;
+        Ok(Spectrum {
+            start_nm,
+            step_nm,
+            values,
+        })
+    }
+
+    /// Value at `nm`, if it's on the axis.
+    pub fn at(&self, nm: u16) -> Option<f32> {
+        let offset = nm.checked_sub(self.start_nm)?;
+        if offset.checked_rem(self.step_nm)? != 0 {
+            return None;
+        }
+        self.values.get(usize::from(offset / self.step_nm)).copied()
+    }
+
+    /// `(wavelength in nm, value)` pairs.
+    pub fn iter_nm(&self) -> impl Iterator<Item = (u16, f32)> + '_ {
+        let len = if self.step_nm == 0 {
+            0
+        } else {
+            self.values.len()
+        };
+        (self.start_nm..=u16::MAX)
+            .step_by(self.step_nm.max(1).into())
+            .zip(self.values[..len].iter().copied())
+    }
+
+    /// Wavelength with the highest value.
+    pub fn peak_wavelength(&self) -> Option<u16> {
+        self.iter_nm()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(nm, _)| nm)
+    }
+
+    /// Photosynthetic photon flux density in µmol/(m²·s), counting photons
+    /// from 400nm to 700nm. Assumes the values are irradiance in W/(m²·nm).
+    pub fn ppfd(&self) -> f32 {
+        // h * c * N_A, in J·nm/µmol
+        const HC_NA: f64 = 119.627;
+        self.iter_nm()
+            .filter(|(nm, _)| (400..=700).contains(nm))
+            .map(|(nm, value)| f64::from(value) * f64::from(nm) * f64::from(self.step_nm) / HC_NA)
+            .sum::<f64>() as f32
+    }
+
+    /// Illuminance in lux, from the photopic luminous efficiency
+    /// [`color::V_LAMBDA`]. Assumes the values are irradiance in W/(m²·nm),
+    /// and ignores anything outside 380-780nm.
+    pub fn lux(&self) -> f32 {
+        let sum: f64 = self
+            .iter_nm()
+            .filter_map(|(nm, value)| {
+                let v = color::V_LAMBDA.get(usize::from(nm.checked_sub(color::TABLE_START_NM)?))?;
+                Some(f64::from(value) * v * f64::from(self.step_nm))
+            })
+            .sum();
+        (sum * color::K_M) as f32
+    }
+}
+
+/// Difference in one metric between two captures, from [`diff_captures`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub name: String,
+    /// Value in the first capture, `None` for metrics that only exist as a
+    /// difference, like the spectrum RMS.
+    pub a: Option<f64>,
+    pub b: Option<f64>,
+    pub delta: f64,
+}
+
+impl FieldDiff {
+    fn new(name: impl Into<String>, a: impl Into<f64>, b: impl Into<f64>) -> Self {
+        let (a, b) = (a.into(), b.into());
+        FieldDiff {
+            name: name.into(),
+            a: Some(a),
+            b: Some(b),
+            delta: b - a,
+        }
+    }
+}
+
+/// Per metric `b - a` for the scalar metrics, and the RMS difference of the
+/// 1nm spectra.
+pub fn diff_captures(a: &CaptureInfo, b: &CaptureInfo) -> Vec<FieldDiff> {
+    let mut ret = vec![
+        FieldDiff::new("cct_k", a.cct_k, b.cct_k),
+        FieldDiff::new("uv", a.uv_angle, b.uv_angle),
+        FieldDiff::new("illuminance_lx", a.illum_lx, b.illum_lx),
+        FieldDiff::new("illuminance_fc", a.illum_fc, b.illum_fc),
+        FieldDiff::new("cie1931_x", a.cie1931_x, b.cie1931_x),
+        FieldDiff::new("cie1931_y", a.cie1931_y, b.cie1931_y),
+        FieldDiff::new("cie1976_u_prime", a.cie1976_up, b.cie1976_up),
+        FieldDiff::new("cie1976_v_prime", a.cie1976_vp, b.cie1976_vp),
+        FieldDiff::new("cri_ra", a.cri_ra, b.cri_ra),
+    ];
+    for (i, (ra, rb)) in a.cri.iter().zip(&b.cri).enumerate() {
+        ret.push(FieldDiff::new(format!("cri_r{}", i + 1), *ra, *rb));
+    }
+
+    let sum_sq: f64 = a
+        .spectral_data_1nm
+        .iter()
+        .zip(&b.spectral_data_1nm)
+        .map(|(a, b)| f64::from(b - a).powi(2))
+        .sum();
+    ret.push(FieldDiff {
+        name: "spectrum_1nm_rms".into(),
+        a: None,
+        b: None,
+        delta: (sum_sq / a.spectral_data_1nm.len() as f64).sqrt(),
+    });
+    ret
+}
+
+// Probably need to name this better, oh well
+// "MEB" structure
+/// The rest of a capture, from the ME response.
+///
+/// The `unk*` and `remaining` fields are unstable in the same way as
+/// [`CaptureInfo`]'s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureData {
+    pub tm_30_rf: f32,
+    pub tm_30_rg: f32,
+    pub illuminants: [[f32; 4]; 16],
+    pub ssit: f32,
+    pub ssid: f32,
+    // unk3-unk6 look like they could be SSI1/SSI2 as reference CCT and value
+    // pairs, but that's a guess. They aren't exported until that's confirmed
+    #[serde(skip)]
+    pub unk3: u32,
+    #[serde(skip)]
+    pub unk4: f32,
+    #[serde(skip)]
+    pub unk5: u32,
+    #[serde(skip)]
+    pub unk6: f32,
+    pub tlci: f32,
+    #[serde(skip)]
+    pub unk8: u32,
+    #[serde(skip)]
+    pub unk9: [f32; 3],
+    #[serde(skip)]
+    pub unk10: u32,
+    #[serde(skip)]
+    pub unk11: u32,
+    // unk2: [f32; 10],
+    // everything after unk11, still comma separated. TLMF might be in here
+    #[serde(skip)]
+    pub remaining: HVec,
+}
+
+impl CaptureData {
+    pub fn parse(i: &[u8]) -> Result<CaptureData> {
+        let mut p = ParseHelper::start(i, "MEB")?;
+        let tm_30_rf = p.float()?;
+        let tm_30_rg = p.float()?;
+        let mut illuminants = [[0.; 4]; 16];
+        for row in &mut illuminants {
+            for val in row {
+                *val = p.float()?;
+            }
+        }
+        // let mut unk2 = [0.; 10];
+        // for u in &mut unk2 {
+        //     *u = p.float().unwrap();
+        // }
+        let cd = CaptureData {
+            tm_30_rf,
+            tm_30_rg,
+            illuminants,
+            ssit: p.float()?,
+            ssid: p.float()?,
+            unk3: p.required_unsigned("ME unk3")?,
+            unk4: p.float()?,
+            unk5: p.required_unsigned("ME unk5")?,
+            unk6: p.float()?,
+            tlci: p.float()?,
+            unk8: p.required_unsigned("ME unk8")?,
+            unk9: [p.float()?, p.float()?, p.float()?],
+            unk10: p.required_unsigned("ME unk10")?,
+            unk11: p.required_unsigned("ME unk11")?,
+            remaining: p.remaining.to_owned().into(),
+        };
+        debug!(
+            "{} bytes left in the ME response: {:?}",
+            cd.remaining.0.len(),
+            cd.remaining
+        );
+        Ok(cd)
+    }
+
+    /// The undecoded tail of the response, after the last known field.
+    pub fn remaining(&self) -> &[u8] {
+        &self.remaining.0
+    }
+
+    // everything zero, like CaptureInfo::empty
+    fn empty() -> CaptureData {
+        CaptureData {
+            tm_30_rf: 0.,
+            tm_30_rg: 0.,
+            illuminants: [[0.; 4]; 16],
+            ssit: 0.,
+            ssid: 0.,
+            unk3: 0,
+            unk4: 0.,
+            unk5: 0,
+            unk6: 0.,
+            tlci: 0.,
+            unk8: 0,
+            unk9: [0.; 3],
+            unk10: 0,
+            unk11: 0,
+            remaining: HVec::default(),
+        }
+    }
+
+    /// [`CaptureData::illuminants`] with the columns named, one per TM-30 hue
+    /// bin starting from bin 1.
+    pub fn color_vector_bins(&self) -> [ColorVectorBin; 16] {
+        self.illuminants
+            .map(|[ref_x, ref_y, meas_x, meas_y]| ColorVectorBin {
+                ref_x,
+                ref_y,
+                meas_x,
+                meas_y,
+            })
+    }
+}
+
+/// One hue bin of the TM-30 color vector graphic: where the bin's average
+/// color sits under the reference illuminant and under the measured light.
+///
+/// Which space the coordinates are in isn't documented. The methods assume
+/// the usual layout of the graphic, with the reference points around the
+/// origin.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColorVectorBin {
+    pub ref_x: f32,
+    pub ref_y: f32,
+    pub meas_x: f32,
+    pub meas_y: f32,
+}
+
+impl ColorVectorBin {
+    /// Hue angle of the reference point in degrees, 0-360 counterclockwise
+    /// from the x axis.
+    pub fn hue_angle(&self) -> f32 {
+        self.ref_y.atan2(self.ref_x).to_degrees().rem_euclid(360.)
+    }
+
+    /// Relative change in chroma from the reference to the measured point,
+    /// like TM-30's Rcs,h: negative when the light desaturates the bin.
+    pub fn chroma_shift(&self) -> f32 {
+        let reference = self.ref_x.hypot(self.ref_y);
+        self.meas_x.hypot(self.meas_y) / reference - 1.
+    }
+}
+
+/// One measurement: MR and ME describe the same capture, this keeps them
+/// together along with where it sits on the meter.
+///
+/// Serializes with the decoded fields only, the `unk*` ones and any
+/// undecoded tail come back zeroed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capture {
+    pub info: CaptureInfo,
+    pub data: CaptureData,
+    pub title: String,
+    /// Index within its title, 1 indexed.
+    pub local_index: u32,
+}
+
+impl Capture {
+    /// Put together a capture from an info fetched earlier, like during a scan.
+    pub fn new(info: CaptureInfo, data: CaptureData, local_index: u32) -> Capture {
+        Capture {
+            title: info.title.clone(),
+            info,
+            data,
+            local_index,
+        }
+    }
+
+    /// Save as JSON, for [`Capture::from_file`] to load without the meter.
+    /// Values round trip exactly, except NaN which JSON has no way to write.
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        write_atomically(path, |f| Ok(serde_json::to_writer_pretty(f, self)?))?;
+        Ok(())
+    }
+
+    pub fn from_file(path: &Path) -> Result<Capture> {
+        Ok(serde_json::from_slice(&fs::read(path)?).map_err(std::io::Error::from)?)
+    }
+
+    /// Fetch both halves of `global_id`. The meter doesn't report the local
+    /// index with each capture, so it has to come from the caller.
+    pub fn fetch<T: Transport>(
+        device: &mut SekonicDevice<T>,
+        global_id: u32,
+        local_index: u32,
+    ) -> Result<Capture> {
+        Ok(Capture::new(
+            device.capture_info(global_id)?,
+            device.capture_data(global_id)?,
+            local_index,
+        ))
+    }
+
+    /// The first measurement that's NaN or infinite, if any. The meter
+    /// doesn't send those, so one means the response was parsed at the wrong
+    /// offsets.
+    pub fn non_finite_field(&self) -> Option<&'static str> {
+        let (ci, cd) = (&self.info, &self.data);
+        let tristimulus = [ci.tristimulus_x, ci.tristimulus_y, ci.tristimulus_z];
+        if tristimulus.iter().any(|v| !v.is_finite()) {
+            return Some("tristimulus value");
+        }
+        let fields: [(&'static str, &[f32]); 21] = [
+            ("CCT", &[ci.cct_k]),
+            ("Duv", &[ci.uv_angle]),
+            ("illuminance [lx]", &[ci.illum_lx]),
+            ("illuminance [fc]", &[ci.illum_fc]),
+            ("CIE1931 x", &[ci.cie1931_x]),
+            ("CIE1931 y", &[ci.cie1931_y]),
+            ("CIE1976 u'", &[ci.cie1976_up]),
+            ("CIE1976 v'", &[ci.cie1976_vp]),
+            ("dominant wavelength", &[ci.dominant_wavelength]),
+            ("purity", &[ci.purity]),
+            ("PPFD", &[ci.ppfd]),
+            ("CRI Ra", &[ci.cri_ra]),
+            ("CRI R1-R15", &ci.cri),
+            ("5nm spectrum", &ci.spectral_data_5nm),
+            ("1nm spectrum", &ci.spectral_data_1nm),
+            ("TM-30 Rf", &[cd.tm_30_rf]),
+            ("TM-30 Rg", &[cd.tm_30_rg]),
+            ("TM-30 color vector bins", cd.illuminants.as_flattened()),
+            ("SSIt", &[cd.ssit]),
+            ("SSId", &[cd.ssid]),
+            ("TLCI", &[cd.tlci]),
+        ];
+        fields
+            .iter()
+            .find(|(_, values)| values.iter().any(|v| !v.is_finite()))
+            .map(|&(name, _)| name)
+    }
+}
+
+/// The mean of several captures of the same source, from [`average_captures`].
+#[derive(Debug, Clone)]
+pub struct AveragedCapture {
+    pub capture: Capture,
+    /// How many captures went into it.
+    pub count: usize,
+    /// Sample standard deviation of the CCTs the meter reported, in K. 0 for
+    /// a single capture.
+    pub cct_std_dev_k: f32,
+}
+
+/// Average several captures of the same source, to even out the noise in
+/// low light. `None` if there aren't any.
+///
+/// Illuminance, PPFD, the tristimulus values and both spectra are plain
+/// means. Chromaticity, CCT, Duv, dominant wavelength and purity are worked
+/// out again from the mean XYZ with `observer`, which should be the one the
+/// meter was set to, since averaging x,y or CCTs directly is off for anything
+/// but very close values. CRI, TM-30, SSI and TLCI aren't linear in the
+/// spectrum, so their plain means are only close to what the mean spectrum
+/// would give. The title, local index and undecoded fields can't be averaged
+/// and are the first capture's.
+pub fn average_captures(
+    captures: &[Capture],
+    observer: color::Observer,
+) -> Option<AveragedCapture> {
+    let first = captures.first()?;
+    let n = captures.len() as f64;
+    let mean = |value: &dyn Fn(&Capture) -> f64| captures.iter().map(value).sum::<f64>() / n;
+    let mean_f32 = |value: &dyn Fn(&Capture) -> f32| mean(&|c| value(c).into()) as f32;
+
+    let mut ret = first.clone();
+    let (ci, cd) = (&mut ret.info, &mut ret.data);
+    ci.illum_lx = mean_f32(&|c| c.info.illum_lx);
+    ci.illum_fc = mean_f32(&|c| c.info.illum_fc);
+    ci.ppfd = mean_f32(&|c| c.info.ppfd);
+    ci.tristimulus_x = mean(&|c| c.info.tristimulus_x);
+    ci.tristimulus_y = mean(&|c| c.info.tristimulus_y);
+    ci.tristimulus_z = mean(&|c| c.info.tristimulus_z);
+    for (i, v) in ci.spectral_data_5nm.iter_mut().enumerate() {
+        *v = mean_f32(&|c| c.info.spectral_data_5nm[i]);
+    }
+    for (i, v) in ci.spectral_data_1nm.iter_mut().enumerate() {
+        *v = mean_f32(&|c| c.info.spectral_data_1nm[i]);
+    }
+
+    let xyz = color::Xyz {
+        x: ci.tristimulus_x,
+        y: ci.tristimulus_y,
+        z: ci.tristimulus_z,
+    };
+    let (x, y) = xyz.chromaticity();
+    let (u, v) = xyz.uv_1960();
+    (ci.cie1931_x, ci.cie1931_y) = (x as f32, y as f32);
+    // u' is the 1960 u, v' is 1.5 times the 1960 v
+    (ci.cie1976_up, ci.cie1976_vp) = (u as f32, (1.5 * v) as f32);
+    (ci.cct_k, ci.uv_angle) = xyz.cct_duv(observer);
+    (ci.dominant_wavelength, ci.purity) =
+        color::dominant_wavelength_purity(ci.cie1931_x, ci.cie1931_y, observer);
+
+    ci.cri_ra = mean_f32(&|c| c.info.cri_ra);
+    for (i, v) in ci.cri.iter_mut().enumerate() {
+        *v = mean_f32(&|c| c.info.cri[i]);
+    }
+    cd.tm_30_rf = mean_f32(&|c| c.data.tm_30_rf);
+    cd.tm_30_rg = mean_f32(&|c| c.data.tm_30_rg);
+    for (i, bin) in cd.illuminants.iter_mut().enumerate() {
+        for (j, v) in bin.iter_mut().enumerate() {
+            *v = mean_f32(&|c| c.data.illuminants[i][j]);
+        }
+    }
+    cd.ssit = mean_f32(&|c| c.data.ssit);
+    cd.ssid = mean_f32(&|c| c.data.ssid);
+    cd.tlci = mean_f32(&|c| c.data.tlci);
+
+    let cct_mean = mean(&|c| c.info.cct_k.into());
+    let cct_var = captures
+        .iter()
+        .map(|c| (f64::from(c.info.cct_k) - cct_mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.).max(1.);
+    Some(AveragedCapture {
+        capture: ret,
+        count: captures.len(),
+        cct_std_dev_k: cct_var.sqrt() as f32,
+    })
+}
+
+// Write to a temporary file next to `path` and rename it over `path` once that
+// worked, so a full disk or a crash part way doesn't leave half a file there.
+pub(crate) fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let mut tmp_name = OsString::from(".");
+    tmp_name.push(path.file_name().unwrap_or_default());
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+
+    let res = (|| {
+        let mut f = BufWriter::new(File::create(&tmp)?);
+        write(&mut f)?;
+        f.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    res
+}
+
+/// Layout of [`write_csv`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvStyle {
+    /// Sections separated by blank lines and each metric at the precision
+    /// Sekonic's C-7000 Utility uses, for tools that read its exports.
+    #[default]
+    Sekonic,
+    /// A `metric,value` header and one row per field, with the keys of
+    /// [`write_kv`] and every value at full precision.
+    Normalized,
+}
+
+/// How the spectral sections of a [`CsvStyle::Sekonic`] CSV are laid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpectrumLayout {
+    /// A `Spectral Data {nm}[nm],{value}` row per wavelength, like Sekonic's
+    /// export.
+    #[default]
+    Rows,
+    /// A `wavelength,value` header over plain `{nm},{value}` rows, for
+    /// plotting tools like gnuplot.
+    Columns,
+}
+
+/// How [`write_csv`] and [`write_combined_csv`] lay out and encode their
+/// output. The default matches Sekonic's export.
+#[derive(Debug, Clone, Default)]
+pub struct CsvOptions {
+    /// Only used by [`write_csv`].
+    pub style: CsvStyle,
+    /// Only used by [`write_csv`] with [`CsvStyle::Sekonic`].
+    pub spectrum_layout: SpectrumLayout,
+    /// Start the file with a UTF-8 byte order mark, without which Excel on
+    /// Windows reads it as the local code page.
+    pub bom: bool,
+    /// Spell the labels out in ASCII: `delta_uv` for ⊿uv, `deg` for ° and
+    /// `umol/m2/s` for the PPFD unit. Titles are written as they are.
+    pub ascii: bool,
+    /// Fail on NaN or infinite values instead of leaving them blank. See
+    /// [`Capture::non_finite_field`].
+    pub strict: bool,
+    /// Only write these fields. [`write_csv`] then uses the normalized
+    /// layout whatever `style` is, and [`write_combined_csv`] has a column
+    /// per field keyed the same way.
+    pub fields: Option<FieldSelection>,
+    /// The `Date Saved` to write instead of the time of the export, for
+    /// output that comes out the same each run. When a capture was taken
+    /// isn't decoded yet.
+    pub saved_at: Option<chrono::NaiveDateTime>,
+}
+
+impl CsvOptions {
+    fn check(&self, capture: &Capture) -> anyhow::Result<()> {
+        if let Some(field) = capture.non_finite_field().filter(|_| self.strict) {
+            anyhow::bail!(
+                "capture \"{}\" has a non-finite {field}, it was likely parsed wrong",
+                capture.title
+            );
+        }
+        Ok(())
+    }
+
+    fn start(&self, mut f: impl Write) -> std::io::Result<()> {
+        if self.bom {
+            f.write_all("\u{feff}".as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn duv(&self) -> &'static str {
+        if self.ascii {
+            "delta_uv"
+        } else {
+            "⊿uv"
+        }
+    }
+
+    fn deg(&self) -> &'static str {
+        if self.ascii {
+            "deg"
+        } else {
+            "°"
+        }
+    }
+
+    fn ppfd_unit(&self) -> &'static str {
+        if self.ascii {
+            "umol/m2/s"
+        } else {
+            "umolm⁻²s⁻¹"
+        }
+    }
+}
+
+/// Write a capture laid out like the CSV from Sekonic's C-7000 Utility.
+///
+/// The viewing angle in the title and the measuring mode come from
+/// `settings`; [`Settings::default`] gives ambient and 2°, which is what
+/// this wrote before it knew about settings.
+///
+/// The file only shows up at `path` once it's been written in full.
+pub fn write_csv(
+    capture: &Capture,
+    settings: &Settings,
+    options: &CsvOptions,
+    path: &Path,
+) -> anyhow::Result<()> {
+    options.check(capture)?;
+    write_atomically(path, |f| write_csv_to(f, capture, settings, options))?;
+    Ok(())
+}
+
+fn write_csv_to(
+    mut f: impl Write,
+    capture: &Capture,
+    settings: &Settings,
+    options: &CsvOptions,
+) -> std::io::Result<()> {
+    let (ci, cd) = (&capture.info, &capture.data);
+    options.start(&mut f)?;
+    // the meter's current setting is only a guess at what the capture used,
+    // which is likely one of the early unk integers in MR but hasn't been
+    // worked out
+    let mode = settings.measuring_mode;
+    if let Some(fields) = &options.fields {
+        writeln!(f, "metric,value")?;
+        for (key, value) in fields.select(flat_fields(capture)) {
+            writeln!(f, "{key},{}", csv_escape(&value))?;
+        }
+        return Ok(());
+    }
+    if options.style == CsvStyle::Normalized {
+        writeln!(f, "metric,value")?;
+        writeln!(f, "measuring_mode,{mode}")?;
+        writeln!(f, "viewing_angle_deg,{}", settings.viewing_angle.degrees())?;
+        for (key, value) in flat_fields(capture) {
+            writeln!(f, "{key},{}", csv_escape(&value))?;
+        }
+        return Ok(());
+    }
+    // when the capture was taken is likely somewhere in MR (unk5 and unk7 are
+    // the obvious candidates), but until it's decoded the export time is all
+    // there is
+    let date = options
+        .saved_at
+        .unwrap_or_else(|| chrono::offset::Local::now().naive_local());
+    writeln!(f, "Date Saved,{}", date.format("%Y/%m/%d %H:%M:%S"))?;
+    writeln!(
+        f,
+        "Title,{}_{:03}_{:02}{}_{}K",
+        capture.title,
+        capture.local_index,
+        settings.viewing_angle.degrees(),
+        options.deg(),
+        Precision::Whole.format(ci.cct_k)
+    )?;
+    writeln!(f, "Measuring Mode,{mode}")?;
+    writeln!(
+        f,
+        "Viewing Angle,{}{}",
+        settings.viewing_angle.degrees(),
+        options.deg()
+    )?;
+    writeln!(f)?;
+    // blank where a dark capture has nothing meaningful
+    let dark = ci.is_dark();
+    let color = |v: f32, precision: Precision| {
+        if dark {
+            String::new()
+        } else {
+            precision.format(v)
+        }
+    };
+    writeln!(f, "CCT [K],{}", color(ci.cct_k, Precision::Whole))?;
+    writeln!(
+        f,
+        "{},{}",
+        options.duv(),
+        color(ci.uv_angle, Precision::Chromaticity)
+    )?;
+    writeln!(
+        f,
+        "Illuminance [lx],{}",
+        Precision::Whole.format(ci.illuminance_lux())
+    )?;
+    writeln!(
+        f,
+        "Illuminance [fc],{}",
+        Precision::Tenths.format(ci.illuminance_fc())
+    )?;
+    let peak = ci.spectrum_1nm().peak_wavelength().filter(|_| !dark);
+    writeln!(f, "Peak Wavelength [nm],{}", opt(peak))?;
+    writeln!(
+        f,
+        "Tristimulus Value X,{}",
+        Precision::Chromaticity.format(ci.tristimulus_x)
+    )?;
+    writeln!(
+        f,
+        "Tristimulus Value Y,{}",
+        Precision::Chromaticity.format(ci.tristimulus_y)
+    )?;
+    writeln!(
+        f,
+        "Tristimulus Value Z,{}",
+        Precision::Chromaticity.format(ci.tristimulus_z)
+    )?;
+    writeln!(
+        f,
+        "CIE1931 x,{}",
+        color(ci.cie1931_x, Precision::Chromaticity)
+    )?;
+    writeln!(
+        f,
+        "CIE1931 y,{}",
+        color(ci.cie1931_y, Precision::Chromaticity)
+    )?;
+    writeln!(
+        f,
+        "CIE1931 z,{}",
+        color(ci.cie1931_z(), Precision::Chromaticity)
+    )?;
+    writeln!(
+        f,
+        "CIE1976 u',{}",
+        color(ci.cie1976_up, Precision::Chromaticity)
+    )?;
+    writeln!(
+        f,
+        "CIE1976 v',{}",
+        color(ci.cie1976_vp, Precision::Chromaticity)
+    )?;
+    let (u, v) = ci.cie1960_uv();
+    writeln!(f, "CIE1960 u,{}", color(u, Precision::Chromaticity))?;
+    writeln!(f, "CIE1960 v,{}", color(v, Precision::Chromaticity))?;
+    writeln!(
+        f,
+        "Dominant Wavelength [nm],{}",
+        color(ci.dominant_wavelength, Precision::Whole)
+    )?;
+    writeln!(f, "Purity [%],{}", color(ci.purity, Precision::Tenths))?;
+    writeln!(
+        f,
+        "PPFD [{}],{}",
+        options.ppfd_unit(),
+        Precision::Tenths.format(ci.ppfd)
+    )?;
+    writeln!(f, "CRI Ra,{}", color(ci.cri_ra, Precision::Tenths))?;
+    for (i, val) in ci.cri.iter().enumerate() {
+        writeln!(f, "CRI R{},{}", i + 1, color(*val, Precision::Tenths))?;
+    }
+    writeln!(f, "TM-30 Rf,{}", color(cd.tm_30_rf, Precision::Whole))?;
+    writeln!(f, "TM-30 Rg,{}", color(cd.tm_30_rg, Precision::Whole))?;
+    writeln!(f, "SSIt,{}", color(cd.ssit, Precision::Whole))?;
+    writeln!(f, "SSId,{}", color(cd.ssid, Precision::Whole))?;
+    writeln!(f, "TLCI,{}", color(cd.tlci, Precision::Whole))?;
+    writeln!(f)?;
+    for spectrum in [ci.spectrum_5nm(), ci.spectrum_1nm()] {
+        if options.spectrum_layout == SpectrumLayout::Columns {
+            writeln!(f, "wavelength,value")?;
+        }
+        for (nm, val) in spectrum.iter_nm() {
+            let val = Precision::Spectral.format(val);
+            match options.spectrum_layout {
+                SpectrumLayout::Rows => writeln!(f, "Spectral Data {nm}[nm],{val}")?,
+                SpectrumLayout::Columns => writeln!(f, "{nm},{val}")?,
+            }
+        }
+        writeln!(f)?;
+    }
+    writeln!(f, "TM-30 Color Vector Graphic,Reference Illuminant x,Reference Illuminant y,Measured Illuminant x,Measured Illuminant y")?;
+    for (i, bin) in cd.color_vector_bins().iter().enumerate() {
+        writeln!(
+            f,
+            "bin{},{},{},{},{}",
+            i + 1,
+            color(bin.ref_x, Precision::ColorVector),
+            color(bin.ref_y, Precision::ColorVector),
+            color(bin.meas_x, Precision::ColorVector),
+            color(bin.meas_y, Precision::ColorVector)
+        )?;
+    }
+    Ok(())
+}
+
+/// Write several captures into one CSV, with a header row of metric names and
+/// a row per capture. Rows are `(global id, capture)`.
+/// `spectra` adds a column per 5nm spectral bin.
+pub fn write_combined_csv(
+    rows: &[(u32, Capture)],
+    spectra: bool,
+    options: &CsvOptions,
+    path: &Path,
+) -> anyhow::Result<()> {
+    for (_, capture) in rows {
+        options.check(capture)?;
+    }
+    write_atomically(path, |f| write_combined_csv_to(f, rows, spectra, options))?;
+    Ok(())
+}
+
+fn write_combined_csv_to(
+    mut f: impl Write,
+    rows: &[(u32, Capture)],
+    spectra: bool,
+    options: &CsvOptions,
+) -> std::io::Result<()> {
+    options.start(&mut f)?;
+    if let Some(fields) = &options.fields {
+        write!(f, "global_index")?;
+        for key in field_names().iter().filter(|k| fields.contains(k)) {
+            write!(f, ",{key}")?;
+        }
+        writeln!(f)?;
+        for (global_id, capture) in rows {
+            write!(f, "{global_id}")?;
+            for (_, value) in fields.select(flat_fields(capture)) {
+                write!(f, ",{}", csv_escape(&value))?;
+            }
+            writeln!(f)?;
+        }
+        return Ok(());
+    }
+    write!(
+        f,
+        "Global Index,Title,Local Index,CCT [K],{},Illuminance [lx],Illuminance [fc],\
+         CIE1931 x,CIE1931 y,CIE1931 z,CIE1976 u',CIE1976 v',CIE1960 u,CIE1960 v,CRI Ra",
+        options.duv()
+    )?;
+    for i in 1..=15 {
+        write!(f, ",CRI R{i}")?;
+    }
+    write!(f, ",TM-30 Rf,TM-30 Rg,SSIt,SSId,TLCI")?;
+    if spectra {
+        for nm in (380..=780).step_by(5) {
+            write!(f, ",Spectral Data {nm}[nm]")?;
+        }
+    }
+    writeln!(f)?;
+
+    for (global_id, capture) in rows {
+        let (ci, cd) = (&capture.info, &capture.data);
+        // blank where a dark capture has nothing meaningful, like write_csv
+        let dark = ci.is_dark();
+        let color = |v: f32, precision: Precision| {
+            if dark {
+                String::new()
+            } else {
+                precision.format(v)
+            }
+        };
+        let (u, v) = ci.cie1960_uv();
+        write!(
+            f,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            global_id,
+            csv_escape(&capture.title),
+            capture.local_index,
+            color(ci.cct_k, Precision::Whole),
+            color(ci.uv_angle, Precision::Chromaticity),
+            Precision::Whole.format(ci.illuminance_lux()),
+            Precision::Tenths.format(ci.illuminance_fc()),
+            color(ci.cie1931_x, Precision::Chromaticity),
+            color(ci.cie1931_y, Precision::Chromaticity),
+            color(ci.cie1931_z(), Precision::Chromaticity),
+            color(ci.cie1976_up, Precision::Chromaticity),
+            color(ci.cie1976_vp, Precision::Chromaticity),
+            color(u, Precision::Chromaticity),
+            color(v, Precision::Chromaticity),
+            color(ci.cri_ra, Precision::Tenths)
+        )?;
+        for val in &ci.cri {
+            write!(f, ",{}", color(*val, Precision::Tenths))?;
+        }
+        for val in [cd.tm_30_rf, cd.tm_30_rg, cd.ssit, cd.ssid, cd.tlci] {
+            write!(f, ",{}", color(val, Precision::Whole))?;
+        }
+        if spectra {
+            for val in &ci.spectral_data_5nm {
+                write!(f, ",{}", Precision::Spectral.format(*val))?;
+            }
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+/// How many decimals each kind of metric gets in the CSV exports, so the
+/// same kind of value is written the same way everywhere.
+///
+/// Formatting doesn't depend on the locale: the decimal point is always `.`
+/// and there are no thousands separators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// CCT, illuminance in lx, wavelengths and the TM-30, SSI and TLCI
+    /// indices.
+    Whole,
+    /// Illuminance in fc, purity, PPFD and CRI.
+    Tenths,
+    /// Tristimulus values, chromaticity coordinates and Duv.
+    Chromaticity,
+    /// Coordinates in the TM-30 color vector graphic.
+    ColorVector,
+    /// Spectral irradiance.
+    Spectral,
+}
+
+impl Precision {
+    pub const fn decimals(self) -> usize {
+        match self {
+            Precision::Whole => 0,
+            Precision::Tenths => 1,
+            Precision::Chromaticity => 4,
+            Precision::ColorVector => 7,
+            Precision::Spectral => 12,
+        }
+    }
+
+    /// `v` with [`Precision::decimals`] decimals. NaN or infinity usually
+    /// means a misaligned parse, and some importers reject a literal NaN, so
+    /// non-finite values come out empty.
+    pub fn format<T: Into<f64> + fmt::Display + Copy>(self, v: T) -> String {
+        if v.into().is_finite() {
+            format!("{v:.*}", self.decimals())
+        } else {
+            String::new()
+        }
+    }
+}
+
+// like Precision::format, but with as many digits as it takes
+fn shortest<T: Into<f64> + fmt::Display + Copy>(v: T) -> String {
+    if v.into().is_finite() {
+        v.to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// `s` as one CSV field: quoted, with its quotes doubled, if it would
+/// otherwise break the row up, and as is if not.
+pub fn csv_escape(s: &str) -> std::borrow::Cow<'_, str> {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\"")).into()
+    } else {
+        s.into()
+    }
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    date_saved: String,
+    title: &'a str,
+    local_index: u32,
+    cct_k: f32,
+    uv: f32,
+    illuminance: JsonIlluminance,
+    peak_wavelength_nm: Option<u16>,
+    tristimulus: JsonXyz<f64>,
+    cie1931: JsonXyz<f32>,
+    cie1976: JsonUv,
+    cie1960: JsonUv1960,
+    dominant_wavelength_nm: f32,
+    purity_pct: f32,
+    ppfd: f32,
+    cri: JsonCri<'a>,
+    tm_30: JsonTm30,
+    ssi: JsonSsi,
+    tlci: f32,
+    spectral_data_5nm: BTreeMap<u16, f32>,
+    spectral_data_1nm: BTreeMap<u16, f32>,
+}
+
+#[derive(Serialize)]
+struct JsonIlluminance {
+    lx: f32,
+    fc: f32,
+}
+
+#[derive(Serialize)]
+struct JsonXyz<T> {
+    x: T,
+    y: T,
+    z: T,
+}
+
+#[derive(Serialize)]
+struct JsonUv {
+    u_prime: f32,
+    v_prime: f32,
+}
+
+#[derive(Serialize)]
+struct JsonUv1960 {
+    u: f32,
+    v: f32,
+}
+
+#[derive(Serialize)]
+struct JsonCri<'a> {
+    ra: f32,
+    // R1 through R15
+    r: &'a [f32],
+}
+
+#[derive(Serialize)]
+struct JsonTm30 {
+    rf: f32,
+    rg: f32,
+    color_vector_graphic: Vec<JsonColorVectorBin>,
+}
+
+#[derive(Serialize)]
+struct JsonColorVectorBin {
+    reference_x: f32,
+    reference_y: f32,
+    measured_x: f32,
+    measured_y: f32,
+}
+
+#[derive(Serialize)]
+struct JsonSsi {
+    ssit: f32,
+    ssid: f32,
+}
+
+#[derive(Serialize)]
+struct JsonAllExport<'a> {
+    #[serde(flatten)]
+    device: &'a DeviceMetadata,
+    captures: Vec<JsonExport<'a>>,
+}
+
+/// Same fields as [`write_csv`], as one JSON object. Spectral data is keyed by
+/// wavelength in nm.
+pub fn write_json(capture: &Capture, path: &Path) -> anyhow::Result<()> {
+    write_atomically(path, |f| {
+        Ok(serde_json::to_writer_pretty(f, &json_export(capture))?)
+    })?;
+    Ok(())
+}
+
+/// Every capture in one JSON file, as an array of the objects [`write_json`]
+/// writes under `captures`, next to the `serial`, `model` and `firmware` of
+/// `device`.
+pub fn write_all_json(
+    captures: &[Capture],
+    device: &DeviceMetadata,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let export = JsonAllExport {
+        device,
+        captures: captures.iter().map(json_export).collect(),
+    };
+    write_atomically(path, |f| Ok(serde_json::to_writer_pretty(f, &export)?))?;
+    Ok(())
+}
+
+fn json_export(capture: &Capture) -> JsonExport<'_> {
+    let (ci, cd) = (&capture.info, &capture.data);
+    JsonExport {
+        // the export time, like in write_csv
+        date_saved: chrono::offset::Local::now().to_rfc3339(),
+        title: &capture.title,
+        local_index: capture.local_index,
+        cct_k: ci.cct_k,
+        uv: ci.uv_angle,
+        illuminance: JsonIlluminance {
+            lx: ci.illum_lx,
+            fc: ci.illum_fc,
+        },
+        peak_wavelength_nm: ci.spectrum_1nm().peak_wavelength(),
+        tristimulus: JsonXyz {
+            x: ci.tristimulus_x,
+            y: ci.tristimulus_y,
+            z: ci.tristimulus_z,
+        },
+        cie1931: JsonXyz {
+            x: ci.cie1931_x,
+            y: ci.cie1931_y,
+            z: ci.cie1931_z(),
+        },
+        cie1976: JsonUv {
+            u_prime: ci.cie1976_up,
+            v_prime: ci.cie1976_vp,
+        },
+        cie1960: {
+            let (u, v) = ci.cie1960_uv();
+            JsonUv1960 { u, v }
+        },
+        dominant_wavelength_nm: ci.dominant_wavelength,
+        purity_pct: ci.purity,
+        ppfd: ci.ppfd,
+        cri: JsonCri {
+            ra: ci.cri_ra,
+            r: &ci.cri,
+        },
+        tm_30: JsonTm30 {
+            rf: cd.tm_30_rf,
+            rg: cd.tm_30_rg,
+            color_vector_graphic: cd
+                .illuminants
+                .iter()
+                .map(|b| JsonColorVectorBin {
+                    reference_x: b[0],
+                    reference_y: b[1],
+                    measured_x: b[2],
+                    measured_y: b[3],
+                })
+                .collect(),
+        },
+        ssi: JsonSsi {
+            ssit: cd.ssit,
+            ssid: cd.ssid,
+        },
+        tlci: cd.tlci,
+        spectral_data_5nm: ci.spectrum_5nm().iter_nm().collect(),
+        spectral_data_1nm: ci.spectrum_1nm().iter_nm().collect(),
+    }
+}
+
+/// Write the 1nm spectrum as an IES TM-27-14 spectral data file (`.spdx`),
+/// normalized so the peak is 1. `model` fills in the catalog number when it's
+/// known.
+pub fn write_spdx(ci: &CaptureInfo, model: Option<&ModelInfo>, path: &Path) -> anyhow::Result<()> {
+    write_atomically(path, |f| write_spdx_to(f, ci, model))?;
+    Ok(())
+}
+
+fn write_spdx_to(
+    mut f: impl Write,
+    ci: &CaptureInfo,
+    model: Option<&ModelInfo>,
+) -> std::io::Result<()> {
+    writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(f, r#"<IESTM2714 xmlns="iestm2714" version="1.0">"#)?;
+    writeln!(f, "  <Header>")?;
+    writeln!(f, "    <Manufacturer>Sekonic</Manufacturer>")?;
+    if let Some(model) = model {
+        writeln!(
+            f,
+            "    <CatalogNumber>{}</CatalogNumber>",
+            xml_escape(&model.model)
+        )?;
+    }
+    writeln!(
+        f,
+        "    <Description>{}</Description>",
+        xml_escape(&ci.title)
+    )?;
+    writeln!(
+        f,
+        "    <FileCreator>{} {}</FileCreator>",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    )?;
+    writeln!(
+        f,
+        "    <DocumentCreationDate>{}</DocumentCreationDate>",
+        chrono::offset::Local::now().format("%Y-%m-%d")
+    )?;
+    writeln!(f, "  </Header>")?;
+    writeln!(f, "  <SpectralDistribution>")?;
+    writeln!(f, "    <SpectralQuantity>relative</SpectralQuantity>")?;
+    let spectrum = ci.spectrum_1nm();
+    let peak = spectrum
+        .values
+        .iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .fold(0., f32::max);
+    // an empty SpectralData isn't a number, so a wavelength that didn't parse
+    // is left out rather than written blank
+    for (nm, value) in spectrum.iter_nm().filter(|(_, v)| v.is_finite()) {
+        let value = if peak > 0. { value / peak } else { value };
+        writeln!(
+            f,
+            r#"    <SpectralData wavelength="{nm}">{value}</SpectralData>"#
+        )?;
+    }
+    writeln!(f, "  </SpectralDistribution>")?;
+    writeln!(f, "</IESTM2714>")?;
+    Ok(())
+}
+
+/// Same fields as [`write_csv`] as flat `key=value` lines, for grep and awk.
+/// Keys are lowercase and stable. Values that aren't known are left empty.
+pub fn write_kv(capture: &Capture, path: &Path) -> anyhow::Result<()> {
+    write_atomically(path, |f| {
+        for (key, value) in flat_fields(capture) {
+            // a title with a line break in it would break the one line per key
+            // rule
+            writeln!(f, "{key}={}", value.replace(['\r', '\n'], " "))?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Some of the fields of [`write_kv`], by key, for exports that only need a
+/// few metrics. `spectrum_5nm` and `spectrum_1nm` stand for every bin of
+/// that spectrum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelection {
+    names: Vec<String>,
+}
+
+impl FieldSelection {
+    /// Shorter names accepted for some of the keys, as (alias, key). The
+    /// output is still keyed by the key.
+    pub const ALIASES: [(&'static str, &'static str); 2] =
+        [("cct", "cct_k"), ("lux", "illuminance_lx")];
+
+    /// Parse a comma separated list like `cct_k,duv,illuminance_lx,cri_ra`,
+    /// or with [`FieldSelection::ALIASES`] `cct,duv,lux,cri_ra`. Fails on a
+    /// name that isn't a field, listing the ones that are.
+    pub fn parse(list: &str) -> anyhow::Result<FieldSelection> {
+        let known = field_names();
+        let mut names = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let name = FieldSelection::ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == name)
+                .map_or(name, |(_, key)| key);
+            let group = name == "spectrum_5nm" || name == "spectrum_1nm";
+            if !group && !known.iter().any(|k| k == name) {
+                // every spectral bin would drown out the rest
+                let valid: Vec<_> = known
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|k| !k.starts_with("spectrum_"))
+                    .chain(["spectrum_5nm", "spectrum_1nm"])
+                    .collect();
+                let aliases: Vec<_> = FieldSelection::ALIASES
+                    .iter()
+                    .map(|(alias, key)| format!("{alias} for {key}"))
+                    .collect();
+                anyhow::bail!(
+                    "unknown field {name:?}, expected one of {} (or {})",
+                    valid.join(", "),
+                    aliases.join(", ")
+                );
+            }
+            names.push(name.to_owned());
+        }
+        if names.is_empty() {
+            anyhow::bail!("no fields selected");
+        }
+        Ok(FieldSelection { names })
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.names.iter().any(|n| match n.as_str() {
+            "spectrum_5nm" => key.starts_with("spectrum_5nm_"),
+            "spectrum_1nm" => key
+                .strip_prefix("spectrum_")
+                .is_some_and(|nm| nm.bytes().all(|b| b.is_ascii_digit())),
+            n => n == key,
+        })
+    }
+
+    // the selected ones of flat_fields, keeping its order
+    fn select(&self, fields: Vec<(String, String)>) -> impl Iterator<Item = (String, String)> + '_ {
+        fields.into_iter().filter(|(key, _)| self.contains(key))
+    }
+}
+
+/// Only the `fields` of a capture, as one flat JSON object keyed like
+/// [`write_kv`]. Numbers are written as numbers and unknown values as null.
+pub fn write_selected_json(
+    capture: &Capture,
+    fields: &FieldSelection,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let object: serde_json::Map<String, serde_json::Value> = fields
+        .select(flat_fields(capture))
+        .map(|(key, value)| {
+            let value = if value.is_empty() {
+                serde_json::Value::Null
+            } else if let Some(n) = value
+                .parse()
+                .ok()
+                .filter(|_| key != "title")
+                .and_then(serde_json::Number::from_f64)
+            {
+                serde_json::Value::Number(n)
+            } else {
+                serde_json::Value::String(value)
+            };
+            (key, value)
+        })
+        .collect();
+    write_atomically(path, |f| Ok(serde_json::to_writer_pretty(f, &object)?))?;
+    Ok(())
+}
+
+// keys of flat_fields, the same for every capture
+fn field_names() -> Vec<String> {
+    let capture = Capture::new(CaptureInfo::empty(), CaptureData::empty(), 1);
+    flat_fields(&capture)
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect()
+}
+
+// (key, value) for every field, in the order of write_csv. The keys are
+// write_kv's and the normalized CSV's, so they can't change
+fn flat_fields(capture: &Capture) -> Vec<(String, String)> {
+    let (ci, cd) = (&capture.info, &capture.data);
+    let mut ret = Vec::new();
+    let mut push = |key: String, value: String| ret.push((key, value));
+    // empty where a dark capture has nothing meaningful
+    let dark = ci.is_dark();
+    let color = |v: f32| if dark { String::new() } else { shortest(v) };
+    push("title".into(), capture.title.clone());
+    push("local_index".into(), capture.local_index.to_string());
+    push("cct_k".into(), color(ci.cct_k));
+    push("duv".into(), color(ci.uv_angle));
+    push("illuminance_lx".into(), shortest(ci.illuminance_lux()));
+    push("illuminance_fc".into(), shortest(ci.illuminance_fc()));
+    let peak = ci.spectrum_1nm().peak_wavelength().filter(|_| !dark);
+    push("peak_wavelength_nm".into(), opt(peak));
+    push("tristimulus_x".into(), shortest(ci.tristimulus_x));
+    push("tristimulus_y".into(), shortest(ci.tristimulus_y));
+    push("tristimulus_z".into(), shortest(ci.tristimulus_z));
+    push("cie1931_x".into(), color(ci.cie1931_x));
+    push("cie1931_y".into(), color(ci.cie1931_y));
+    push("cie1931_z".into(), color(ci.cie1931_z()));
+    push("cie1976_up".into(), color(ci.cie1976_up));
+    push("cie1976_vp".into(), color(ci.cie1976_vp));
+    let (u, v) = ci.cie1960_uv();
+    push("cie1960_u".into(), color(u));
+    push("cie1960_v".into(), color(v));
+    push(
+        "dominant_wavelength_nm".into(),
+        color(ci.dominant_wavelength),
+    );
+    push("purity_pct".into(), color(ci.purity));
+    push("ppfd".into(), shortest(ci.ppfd));
+    push("cri_ra".into(), color(ci.cri_ra));
+    for (i, val) in ci.cri.iter().enumerate() {
+        push(format!("cri_r{}", i + 1), color(*val));
+    }
+    push("tm30_rf".into(), color(cd.tm_30_rf));
+    push("tm30_rg".into(), color(cd.tm_30_rg));
+    push("ssit".into(), color(cd.ssit));
+    push("ssid".into(), color(cd.ssid));
+    push("tlci".into(), color(cd.tlci));
+    for (i, bin) in cd.color_vector_bins().iter().enumerate() {
+        let bin_no = i + 1;
+        push(format!("tm30_bin{bin_no}_reference_x"), color(bin.ref_x));
+        push(format!("tm30_bin{bin_no}_reference_y"), color(bin.ref_y));
+        push(format!("tm30_bin{bin_no}_measured_x"), color(bin.meas_x));
+        push(format!("tm30_bin{bin_no}_measured_y"), color(bin.meas_y));
+    }
+    for (nm, val) in ci.spectrum_5nm().iter_nm() {
+        push(format!("spectrum_5nm_{nm}"), shortest(val));
+    }
+    for (nm, val) in ci.spectrum_1nm().iter_nm() {
+        push(format!("spectrum_{nm}"), shortest(val));
+    }
+    ret
+}
+
+// empty when unknown
+fn opt<T: fmt::Display>(v: Option<T>) -> String {
+    v.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Line chart of the 1nm spectrum over 380-780nm, as a standalone SVG. The
+/// y axis runs from 0 to the spectrum's peak.
+pub fn write_spectrum_svg(ci: &CaptureInfo, path: &Path) -> anyhow::Result<()> {
+    write_atomically(path, |f| write_spectrum_svg_to(f, ci))?;
+    Ok(())
+}
+
+fn write_spectrum_svg_to(mut f: impl Write, ci: &CaptureInfo) -> std::io::Result<()> {
+    const WIDTH: f32 = 800.;
+    const HEIGHT: f32 = 400.;
+    // plot area, inside the room left for the labels
+    const LEFT: f32 = 80.;
+    const RIGHT: f32 = WIDTH - 20.;
+    const TOP: f32 = 20.;
+    const BOTTOM: f32 = HEIGHT - 50.;
+    const START_NM: f32 = 380.;
+    const END_NM: f32 = 780.;
+
+    let spectrum = ci.spectrum_1nm();
+    let peak = spectrum
+        .values
+        .iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .fold(0., f32::max);
+    // an all zero spectrum still gets a sensible axis
+    let y_max = if peak > 0. { peak } else { 1. };
+    let x = |nm: f32| LEFT + (nm - START_NM) / (END_NM - START_NM) * (RIGHT - LEFT);
+    let y = |value: f32| BOTTOM - value / y_max * (BOTTOM - TOP);
+
+    writeln!(
+        f,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+    )?;
+    writeln!(f, "  <title>{}</title>", xml_escape(&ci.title))?;
+    writeln!(
+        f,
+        r#"  <rect width="{WIDTH}" height="{HEIGHT}" fill="white"/>"#
+    )?;
+
+    for nm in (400..=750).step_by(50) {
+        let px = x(nm as f32);
+        writeln!(
+            f,
+            r##"  <line x1="{px}" y1="{TOP}" x2="{px}" y2="{BOTTOM}" stroke="#ddd"/>"##
+        )?;
+        writeln!(
+            f,
+            r#"  <text x="{px}" y="{}" text-anchor="middle">{nm}</text>"#,
+            BOTTOM + 16.
+        )?;
+    }
+    for i in 0..=4 {
+        let value = y_max * i as f32 / 4.;
+        let py = y(value);
+        writeln!(
+            f,
+            r##"  <line x1="{LEFT}" y1="{py}" x2="{RIGHT}" y2="{py}" stroke="#ddd"/>"##
+        )?;
+        writeln!(
+            f,
+            r#"  <text x="{}" y="{}" text-anchor="end">{value:.3e}</text>"#,
+            LEFT - 6.,
+            py + 4.
+        )?;
+    }
+    writeln!(
+        f,
+        r#"  <text x="{}" y="{}" text-anchor="middle">Wavelength [nm]</text>"#,
+        (LEFT + RIGHT) / 2.,
+        HEIGHT - 10.
+    )?;
+    writeln!(
+        f,
+        r#"  <text x="16" y="{0}" text-anchor="middle" transform="rotate(-90 16 {0})">Spectral irradiance [W/(m²·nm)]</text>"#,
+        (TOP + BOTTOM) / 2.
+    )?;
+    writeln!(
+        f,
+        r#"  <rect x="{LEFT}" y="{TOP}" width="{}" height="{}" fill="none" stroke="black"/>"#,
+        RIGHT - LEFT,
+        BOTTOM - TOP
+    )?;
+
+    let points: Vec<String> = spectrum
+        .iter_nm()
+        .filter(|(nm, value)| (380..=780).contains(nm) && value.is_finite())
+        .map(|(nm, value)| format!("{:.1},{:.1}", x(f32::from(nm)), y(value)))
+        .collect();
+    writeln!(
+        f,
+        r#"  <polyline points="{}" fill="none" stroke="black" stroke-width="1.5"/>"#,
+        points.join(" ")
+    )?;
+    writeln!(f, "</svg>")?;
+    Ok(())
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&apos;"),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_float_round_trips() {
+        for v in [0., 1., -2.5, 3200.25, 0.000_154_94_f32] {
+            assert_eq!(parse_ascii_float(v.to_string().as_bytes()), Some(v));
+        }
+        assert_eq!(parse_ascii_float(b" 97.2 "), Some(97.2));
+        assert_eq!(parse_ascii_float(b"\x45\x48\0\0"), None);
+    }
+
+    #[test]
+    fn status_frame() {
+        assert!(check_status(&RESP_OK).is_ok());
+        assert!(matches!(
+            check_status(&RESP_BADREQ),
+            Err(SekonicError::BadRequest)
+        ));
+        for frame in [&[][..], &[0x6]] {
+            assert!(matches!(
+                check_status(frame),
+                Err(SekonicError::ShortRead { expected: 2, got }) if got == frame.len()
+            ));
+        }
+        assert!(matches!(
+            check_status(&[0x6, 0x30, 0x41]),
+            Err(SekonicError::UnexpectedResponse(b)) if b == [0x6, 0x30, 0x41]
+        ));
+    }
+
+    #[test]
+    fn spectrum_one_byte_short() {
+        let mut resp = b"XYB@@".to_vec();
+        resp.extend([0x3f; 4 * 81 - 1]);
+        for tail in [&b""[..], b",1"] {
+            let resp = [&resp[..], tail].concat();
+            let mut p = ParseHelper::start(&resp, "XYB").unwrap();
+            assert!(matches!(
+                p.float_array::<81>(),
+                Err(SekonicError::Parse { .. })
+            ));
+        }
+
+        resp.push(0x3f);
+        let mut p = ParseHelper::start(&resp, "XYB").unwrap();
+        assert_eq!(p.float_array::<81>().unwrap().len(), 81);
+    }
+
+    #[test]
+    fn model_info() {
+        let mi = ModelInfo::parse(b"MNB@@C-7000\0,2021,\x01\x02,").unwrap();
+        assert_eq!(mi.model, "C-7000");
+        assert_eq!(mi.text_fields(), ["2021"]);
+
+        assert!(ModelInfo::parse(b"FVB@@C-7000").is_err());
+    }
+
+    #[test]
+    fn firmware_version() {
+        let fv = FirmwareVersion::parse(b"FVB@@1.03\0\0,2021,x").unwrap();
+        assert_eq!(fv.version, "1.03");
+        assert_eq!(fv.to_string(), "1.03");
+        assert_eq!(fv.remaining, [HVec(b"2021".to_vec()), HVec(b"x".to_vec())]);
+        assert_eq!(fv.text_fields(), ["2021", "x"]);
+        assert_eq!(FieldLayout::for_firmware(&fv), &FieldLayout::DEFAULT);
+
+        assert!(FirmwareVersion::parse(b"MNB@@1.03").is_err());
+        assert!(FirmwareVersion::parse(b"FVB@@\xff").is_err());
+    }
+
+    #[test]
+    fn other_separator() {
+        let mut p = ParseHelper::start_with(b"XYB@@12;a,b;\x3f\x80\0\0;7", "XYB", b';').unwrap();
+        assert_eq!(p.unsigned(), Some(12));
+        assert_eq!(p.string().as_deref(), Some("a,b"));
+        assert_eq!(p.float().unwrap(), 1.);
+        assert_eq!(p.collect_remaining(), [HVec(b"7".to_vec())]);
+
+        // a `,` where the `;` should be is a clean error, not a misread
+        let mut p = ParseHelper::start_with(b"XYB@@\x3f\x80\0\0,7", "XYB", b';').unwrap();
+        let e = p.float().unwrap_err().to_string();
+        assert!(e.contains("';'"), "{e}");
+    }
+
+    #[test]
+    fn separator_position() {
+        for (resp, error) in [
+            (&b"XYB@,1"[..], "at byte 3"),
+            (b"XYB@", "too short"),
+            (b"XY", "expected prefix"),
+        ] {
+            let e = ParseHelper::start(resp, "XYB").err().unwrap().to_string();
+            assert!(e.contains(error), "{e}");
+        }
+        assert!(ParseHelper::start(b"XYB@ 1", "XYB").is_ok());
+    }
+
+    #[test]
+    fn precision() {
+        assert_eq!(Precision::Whole.format(2856.4_f32), "2856");
+        assert_eq!(Precision::Whole.format(1234567_f32), "1234567");
+        assert_eq!(Precision::Tenths.format(99.95_f64), "100.0");
+        assert_eq!(Precision::Chromaticity.format(0.31271_f64), "0.3127");
+        assert_eq!(Precision::Chromaticity.format(-0.00005_f64), "-0.0001");
+        assert_eq!(Precision::ColorVector.format(0.5_f32), "0.5000000");
+        assert_eq!(Precision::Spectral.format(1.5e-4_f64), "0.000150000000");
+        assert_eq!(Precision::Whole.format(0_f32), "0");
+        for v in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(Precision::Spectral.format(v), "");
+        }
+    }
+
+    #[test]
+    fn device_model() {
+        assert_eq!(
+            DeviceModel::from_ids(VENDOR_ID, PRODUCT_ID),
+            Some(DeviceModel::C7000)
+        );
+        assert_eq!(DeviceModel::from_ids(0x1234, PRODUCT_ID), None);
+        assert_eq!(DeviceModel::from_ids(VENDOR_ID, 0x7004), None);
+    }
+
+    #[test]
+    fn unknown_prefixes() {
+        for resp in [&b"FTB@@1,abc"[..], b"XY,1,abc", b"", b"\xff\x00"] {
+            FtSetting::parse(resp).unwrap();
+            IuInfo::parse(resp).unwrap();
+        }
+        let ft = FtSetting::parse(b"FTB@@lx,\x01\x02,fc").unwrap();
+        assert_eq!(ft.text_fields(), ["FTB@@lx", "fc"]);
+        let iu = IuInfo::parse(b"IUB@@lx,\x01\x02,fc").unwrap();
+        assert_eq!(iu.text_fields(), ["IUB@@lx", "fc"]);
+    }
+
+    #[test]
+    fn settings_keep_the_payload() {
+        let settings = Settings::parse(b"SAB@@1,\x02,x").unwrap();
+        assert_eq!(settings.raw(), b"SAB@@1,\x02,x");
+        assert_eq!(Settings::parse(b"XY,1").unwrap().raw(), b"XY,1");
+        assert_eq!(settings.measuring_mode, MeasuringMode::Ambient);
+        assert_eq!(settings.viewing_angle, ViewingAngle::TwoDegree);
+    }
+
+    #[test]
+    fn field_aliases() {
+        let fields = FieldSelection::parse("cct, duv,lux,cri_ra").unwrap();
+        assert_eq!(
+            fields,
+            FieldSelection::parse("cct_k,duv,illuminance_lx,cri_ra").unwrap()
+        );
+        assert!(fields.contains("cct_k"));
+        assert!(!fields.contains("cct"));
+
+        let e = FieldSelection::parse("cct,kelvin").unwrap_err().to_string();
+        assert!(e.contains("\"kelvin\""), "{e}");
+        assert!(e.contains("cct_k,") && e.contains("cct for cct_k"), "{e}");
+        assert!(
+            e.contains("spectrum_1nm") && !e.contains("spectrum_5nm_380"),
+            "{e}"
+        );
+    }
+
+    #[test]
+    fn csv_escaping() {
+        assert_eq!(csv_escape("TITLE1"), "TITLE1");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("a\r\nb"), "\"a\r\nb\"");
+    }
+
+    #[test]
+    fn illuminance_agrees() {
+        let mut ci = CaptureInfo::empty();
+        assert!(ci.illuminance_agrees());
+        ci.illum_lx = 512.3;
+        ci.illum_fc = 47.6;
+        assert!(ci.illuminance_agrees());
+        ci.illum_fc = 51.2;
+        assert!(!ci.illuminance_agrees());
+        ci.illum_fc = f32::NAN;
+        assert!(!ci.illuminance_agrees());
+    }
+
+    #[test]
+    fn spectrum_axis() {
+        let spectrum = Spectrum::new(380, 5, vec![1., 2., 3.]).unwrap();
+        assert_eq!(spectrum.at(385), Some(2.));
+        assert_eq!(spectrum.at(386), None);
+        assert_eq!(spectrum.at(395), None);
+        assert_eq!(spectrum.iter_nm().last(), Some((390, 3.)));
+
+        assert!(Spectrum::new(380, 0, vec![1.]).is_err());
+        assert!(Spectrum::new(u16::MAX, 1, vec![1., 2.]).is_err());
+        assert!(Spectrum::new(u16::MAX, 1, vec![1.]).is_ok());
+
+        // put together without new, which mustn't panic
+        let zero_step = Spectrum {
+            start_nm: 380,
+            step_nm: 0,
+            values: vec![1., 2.],
+        };
+        assert_eq!(zero_step.at(380), None);
+        assert_eq!(zero_step.iter_nm().count(), 0);
+        let past_the_end = Spectrum {
+            start_nm: u16::MAX - 1,
+            step_nm: 1,
+            values: vec![1., 2., 3.],
+        };
+        assert_eq!(
+            past_the_end.iter_nm().collect::<Vec<_>>(),
+            [(u16::MAX - 1, 1.), (u16::MAX, 2.)]
+        );
+        assert_eq!(past_the_end.peak_wavelength(), Some(u16::MAX));
+    }
+
+    #[test]
+    fn spectrum_deserialize() {
+        let spectrum: Spectrum =
+            serde_json::from_str(r#"{"start_nm":380,"step_nm":5,"values":[1.0,2.0]}"#).unwrap();
+        assert_eq!(spectrum, Spectrum::new(380, 5, vec![1., 2.]).unwrap());
+
+        let e = serde_json::from_str::<Spectrum>(r#"{"start_nm":380,"step_nm":0,"values":[1.0]}"#)
+            .unwrap_err();
+        assert!(e.to_string().contains("at least 1nm"), "{e}");
+        let e = serde_json::from_str::<Spectrum>(
+            r#"{"start_nm":65535,"step_nm":1,"values":[1.0,2.0]}"#,
+        )
+        .unwrap_err();
+        assert!(e.to_string().contains("65535nm"), "{e}");
+    }
+}
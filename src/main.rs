@@ -1,596 +1,1457 @@
 use std::{
-    array,
     cmp::min,
-    collections::BTreeMap,
-    fmt,
-    fs::File,
-    io::{stdin, Write},
-    path::Path,
-    str,
-    time::Duration,
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, OpenOptions},
+    io::{stderr, stdin, IsTerminal, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
-use anyhow::{bail, format_err};
-use libusb::{DeviceHandle, TransferType};
+use anyhow::{bail, Context as _};
+use clap::{Parser, ValueEnum};
+use log::warn;
 use pretty_hex::PrettyHex;
+use sekonic_c_7000::{
+    average_captures, csv_escape, diff_captures, write_all_json, write_combined_csv, write_csv,
+    write_json, write_kv, write_selected_json, write_spdx, write_spectrum_svg, Capture,
+    CaptureData, CaptureInfo, CsvOptions, CsvStyle, FieldSelection, MockTransport, Precision,
+    RecordingTransport, ScanCache, SekonicDevice, SekonicError, SpectrumLayout, Transport,
+    ViewingAngle, DEFAULT_MAX_ATTEMPTS, DEFAULT_TIMEOUT,
+};
 
-const VENDOR_ID: u16 = 0x0a41;
-const PRODUCT_ID: u16 = 0x7003;
+/// Dump captures off a Sekonic C-7000
+#[derive(Parser)]
+struct Args {
+    /// Global id of the capture to export. Prompts for one if not given
+    #[arg(conflicts_with_all = ["all", "list"])]
+    capture: Option<u32>,
 
-const IN_ENDPOINT_ADDR: u8 = 0x81;
-const OUT_ENDPOINT_ADDR: u8 = 0x2;
+    /// Serial number of the meter to use, when there's more than one plugged in
+    #[arg(long)]
+    serial: Option<String>,
 
-const TIMEOUT: Duration = Duration::from_millis(1000);
+    /// File to write, or the directory to write into with --all or --select
+    /// (unless --combined). A leading ~ is the home directory
+    #[arg(short, long, value_parser = parse_output)]
+    output: Option<PathBuf>,
 
-const RESP_OK: [u8; 2] = [0x6, 0x30];
-const RESP_BADREQ: [u8; 2] = [0x15, 0x32];
+    /// Create the directory the output goes into if it doesn't exist, instead
+    /// of failing or, at the filename prompt, asking
+    #[arg(long)]
+    mkdir: bool,
 
-struct HVec(Vec<u8>);
+    /// USB timeout per transfer
+    #[arg(long, default_value_t = DEFAULT_TIMEOUT.as_millis() as u64)]
+    timeout_ms: u64,
 
-impl fmt::Debug for HVec {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.0.hex_dump())
-    }
+    /// How many times to try a request that times out before giving up
+    #[arg(long, default_value_t = DEFAULT_MAX_ATTEMPTS)]
+    attempts: u32,
+
+    /// Save the raw traffic with the meter into this directory
+    #[arg(long, conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Run against traffic saved with --record instead of a meter
+    #[arg(long)]
+    replay: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+
+    /// Export every capture instead of just one
+    #[arg(long)]
+    all: bool,
+
+    /// With --all, write every capture into one file instead, as the rows of
+    /// a CSV or, with --format json, a JSON array along with the meter's
+    /// serial, model and firmware
+    #[arg(long, requires = "all")]
+    combined: bool,
+
+    /// Export these captures like --all does, as ids and ranges of them like
+    /// 3,5,7-10. Ids that aren't on the meter are reported and skipped
+    #[arg(long, value_name = "IDS", value_parser = parse_selection, conflicts_with_all = ["capture", "all", "list", "compare", "average", "log", "watch"])]
+    select: Option<Selection>,
+
+    /// Include the 5nm spectral data in the --combined CSV
+    #[arg(long, requires = "combined")]
+    spectra: bool,
+
+    /// Start CSV exports with a UTF-8 byte order mark, for Excel on Windows
+    #[arg(long)]
+    bom: bool,
+
+    /// Layout of CSV exports: sekonic for the same layout as Sekonic's
+    /// software, normalized for one metric,value row per field
+    #[arg(long, value_parser = parse_csv_style, default_value = "sekonic")]
+    csv_style: CsvStyle,
+
+    /// Layout of the spectra in sekonic style CSV exports: rows for a
+    /// labeled row per wavelength like Sekonic's software, columns for a
+    /// wavelength,value table
+    #[arg(long, value_parser = parse_spectrum_layout, default_value = "rows")]
+    spectrum_layout: SpectrumLayout,
+
+    /// Use ASCII labels in CSV exports (delta_uv, deg, umol/m2/s) instead of
+    /// the Unicode ones Sekonic's export has
+    #[arg(long)]
+    ascii: bool,
+
+    /// Only export these fields, as keys of --format kv separated by commas
+    /// like cct_k,duv,illuminance_lx,cri_ra. spectrum_5nm and spectrum_1nm
+    /// pick a whole spectrum, and cct and lux are short for cct_k and
+    /// illuminance_lx. Only for csv and json
+    #[arg(long, value_parser = parse_fields)]
+    fields: Option<FieldSelection>,
+
+    /// Print the captures on the meter and exit
+    #[arg(long, conflicts_with = "all")]
+    list: bool,
+
+    /// Print how two captures differ, metric by metric
+    #[arg(long, num_args = 2, value_names = ["A", "B"], conflicts_with_all = ["capture", "all", "list"])]
+    compare: Option<Vec<u32>>,
+
+    /// Average these captures, which should be of the same source, and
+    /// export the result like a single capture. Chromaticity and CCT are
+    /// worked out again from the averaged XYZ
+    #[arg(long, num_args = 2.., value_name = "ID", conflicts_with_all = ["capture", "all", "list", "compare", "combined", "log", "watch"])]
+    average: Option<Vec<u32>>,
+
+    /// Viewing angle to put in CSV exports, in degrees. Defaults to what the
+    /// meter reports
+    #[arg(long, value_parser = parse_viewing_angle)]
+    viewing_angle: Option<ViewingAngle>,
+
+    /// Keep measuring and append a row per measurement to the output CSV,
+    /// until --duration runs out or Ctrl-C
+    #[arg(long, conflicts_with_all = ["capture", "all", "list", "compare"])]
+    log: bool,
+
+    /// Keep checking for new captures and export each one into the output
+    /// directory as it's taken, until Ctrl-C. Survives the meter being
+    /// unplugged and plugged back in
+    #[arg(long, conflicts_with_all = ["capture", "all", "list", "compare", "log", "combined"])]
+    watch: bool,
+
+    /// How often to measure with --log, or to check for new captures with
+    /// --watch
+    #[arg(long, default_value_t = 1000)]
+    interval_ms: u64,
+
+    /// How long to keep logging for, in seconds. Forever if not given
+    #[arg(long, requires = "log")]
+    duration: Option<u64>,
+
+    /// How many times to open the meter again when it's unplugged in the
+    /// middle of a scan or export, before giving up
+    #[arg(long, default_value_t = 3)]
+    reconnects: u32,
+
+    /// How long to wait before each of those, in milliseconds
+    #[arg(long, default_value_t = 2000)]
+    reconnect_delay_ms: u64,
+
+    /// Log more, -v for debug and -vv to also dump every request and response.
+    /// RUST_LOG overrides this
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Show the approximate color of each capture as a hex sRGB value
+    #[arg(long)]
+    swatch: bool,
+
+    /// Also read the rest of each capture while scanning, to list its CRI Ra
+    /// and TM-30 Rf and Rg. Takes twice as many requests
+    #[arg(long)]
+    scan_detail: bool,
+
+    /// Don't print a summary of the chosen capture before exporting it
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Reuse the capture list from the last run if the meter still has the
+    /// same number of captures, instead of reading every capture again
+    #[arg(long)]
+    cache: bool,
+
+    /// Fail instead of warning when the meter's capture counts don't add up,
+    /// when --all would write two captures to the same file, or when a CSV
+    /// would have NaN or infinite values in it
+    #[arg(long)]
+    strict: bool,
+
+    /// With --all, only print the files that would be written without reading
+    /// capture data or writing anything
+    #[arg(long, requires = "all")]
+    dry_run: bool,
+
+    /// Add every capture to this SQLite database instead of exporting files.
+    /// Captures already in it are updated
+    #[cfg(feature = "sqlite")]
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["log", "compare", "all", "capture"])]
+    sqlite: Option<PathBuf>,
+
+    /// Run the setup sequence and read the storage info, reporting how each
+    /// step went, then exit without reading captures or writing files
+    #[arg(long, conflicts_with_all = ["capture", "all", "list", "compare", "log", "plot"])]
+    selftest: bool,
+
+    /// Print how many titles and captures are on the meter and exit, without
+    /// reading any of the captures
+    #[arg(long, conflicts_with_all = ["capture", "all", "list", "compare", "log", "watch", "selftest", "raw"])]
+    count: bool,
+
+    /// Run the setup sequence, send this request (like MR0003) and print the
+    /// raw response, for working out undocumented requests. Anything but the
+    /// requests this tool sends itself needs --yes-i-know
+    #[arg(long, value_name = "CMD", conflicts_with_all = ["capture", "all", "list", "compare", "log", "watch", "selftest"])]
+    raw: Option<String>,
+
+    /// Send a --raw request that isn't known to only read from the meter
+    #[arg(long, requires = "raw")]
+    yes_i_know: bool,
+
+    /// Also write an SVG plot of the spectrum next to each exported file
+    #[arg(long, conflicts_with_all = ["list", "compare", "log", "combined"])]
+    plot: bool,
 }
 
-impl From<Vec<u8>> for HVec {
-    fn from(value: Vec<u8>) -> Self {
-        Self(value)
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+    /// IES TM-27-14 spectral data
+    Spdx,
+    /// key=value lines, for grep and awk
+    Kv,
+    /// Excel workbook with the metrics, spectra and TM-30 bins on separate
+    /// sheets
+    #[cfg(feature = "xlsx")]
+    Xlsx,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Spdx => "spdx",
+            Format::Kv => "txt",
+            #[cfg(feature = "xlsx")]
+            Format::Xlsx => "xlsx",
+        }
     }
 }
 
-fn make_req(h: &mut DeviceHandle, req: &[u8]) -> Vec<u8> {
-    // println!("REQ: {:?}", std::str::from_utf8(req).unwrap());
-    h.write_bulk(OUT_ENDPOINT_ADDR, req, TIMEOUT).unwrap();
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let export = ExportOptions {
+        format: args.format,
+        viewing_angle: args.viewing_angle,
+        mkdir: args.mkdir,
+        csv: CsvOptions {
+            style: args.csv_style,
+            spectrum_layout: args.spectrum_layout,
+            bom: args.bom,
+            ascii: args.ascii,
+            strict: args.strict,
+            fields: args.fields.clone(),
+            saved_at: None,
+        },
+    };
+    if args.fields.is_some() {
+        match args.format {
+            Format::Json if args.combined => bail!("--fields doesn't work with --combined json"),
+            Format::Csv | Format::Json => {}
+            _ => bail!("--fields only works with csv and json"),
+        }
+    }
+    // warnings from the library are worth seeing without having to ask
+    let level = match args.verbose {
+        0 => "warn",
+        1 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(level)).init();
 
-    let mut buf = [0; 8192];
-    let len = h.read_bulk(IN_ENDPOINT_ADDR, &mut buf, TIMEOUT).unwrap();
+    // Stop at the next convenient point so the meter gets released on the way
+    // out. A second Ctrl-C gives up on that.
+    ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            std::process::exit(130);
+        }
+    })?;
 
-    if len != 2 {
-        println!("expected 2 bytes from first bulk in, strange");
-        println!("{:?}", buf[..len].hex_dump());
-        panic!();
+    // before opening anything, so a refused request never gets near the meter
+    if let Some(cmd) = args.raw.as_deref().filter(|_| !args.yes_i_know) {
+        if !is_read_only(cmd) {
+            bail!("{cmd:?} isn't a request known to only read from the meter, pass --yes-i-know to send it anyway");
+        }
+    }
+    let mut dev = open(&args, args.serial.as_deref())?;
+    if args.selftest {
+        return selftest(&mut dev);
+    }
+    dev.start_session()?;
+    if let Some(cmd) = &args.raw {
+        let resp = dev.request(cmd.as_bytes())?;
+        println!("{:?}", resp.hex_dump());
+        dev.end_session()?;
+        return Ok(());
     }
-    let res = [buf[0], buf[1]];
-    match res {
-        RESP_OK => {
-            let len = h.read_bulk(IN_ENDPOINT_ADDR, &mut buf, TIMEOUT).unwrap();
-            // println!("{:?}", buf[..len].hex_dump());
-            Vec::from(&buf[..len])
+    if args.count {
+        let info = dev.storage_info()?;
+        println!("{} titles, {} captures", info.num_titles, info.num_captures);
+        dev.end_session()?;
+        return Ok(());
+    }
+    // to get the same meter back if it's unplugged
+    let serial = args.serial.clone().or_else(|| dev.transport().serial());
+    let serial = serial.as_deref();
+
+    let mut cap_infos = BTreeMap::new();
+    let info = reconnecting(&mut dev, &args, serial, |dev| Ok(dev.storage_info()?))?;
+    let cache_path = if args.cache { cache_path() } else { None };
+    let device_key = match cache_path {
+        Some(_) => Some(reconnecting(&mut dev, &args, serial, |dev| {
+            Ok(dev.device_key()?)
+        })?),
+        None => None,
+    };
+    let cached = cache_path
+        .as_deref()
+        .zip(device_key.as_deref())
+        .and_then(|(path, key)| {
+            ScanCache::load(path).filter(|c| c.matches(key, info.num_captures))
+        });
+
+    if let Some(cache) = cached {
+        for (global_id, local_capture_id, cap_info) in cache.captures(dev.layout())? {
+            // the cache only has the MR half
+            let data = if args.scan_detail {
+                check_interrupted()?;
+                Some(reconnecting(&mut dev, &args, serial, |dev| {
+                    Ok(dev.capture_data(global_id)?)
+                })?)
+            } else {
+                None
+            };
+            print_capture(
+                global_id,
+                &cap_info,
+                local_capture_id,
+                data.as_ref(),
+                args.swatch,
+            );
+            cap_infos.insert(global_id, (cap_info, local_capture_id));
         }
-        RESP_BADREQ => {
-            panic!("bad reqeust")
+    } else {
+        let title_infos = (1..=info.num_titles)
+            .map(|title| reconnecting(&mut dev, &args, serial, |dev| Ok(dev.title_info(title)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if let Err(e) = info.check_titles(&title_infos) {
+            if args.strict {
+                return Err(e.into());
+            }
+            warn!("{e:#}");
         }
-        _ => {
-            panic!("unknown response {:?}", res.hex_dump());
+        let mut cache = device_key
+            .as_deref()
+            .map(|key| ScanCache::new(key, info.num_captures));
+        let mut progress = Progress::new(title_infos.iter().map(|t| t.num_captures).sum());
+        // parse and print each capture on another thread while the next one
+        // is being read. Parsing is microseconds against milliseconds of USB,
+        // so benches/scan.rs measures no difference from that, but a slow
+        // terminal doesn't hold up the reads
+        let layout = dev.layout();
+        thread::scope(|s| -> anyhow::Result<()> {
+            let (tx, rx) = mpsc::sync_channel::<(u32, u32, Vec<u8>, Option<CaptureData>)>(4);
+            let parser = s.spawn(|| -> anyhow::Result<()> {
+                for (global_id, local_capture_id, raw, data) in rx {
+                    let cap_info = CaptureInfo::parse_with(&raw, layout)?;
+                    progress.clear();
+                    print_capture(
+                        global_id,
+                        &cap_info,
+                        local_capture_id,
+                        data.as_ref(),
+                        args.swatch,
+                    );
+                    progress.inc();
+                    if let Some(cache) = &mut cache {
+                        cache.push(global_id, local_capture_id, &raw);
+                    }
+                    cap_infos.insert(global_id, (cap_info, local_capture_id));
+                }
+                Ok(())
+            });
+            let read = (|| {
+                'scan: for (title, title_info) in (1..).zip(&title_infos) {
+                    for local_capture_id in 1..=title_info.num_captures {
+                        check_interrupted()?;
+                        let (global_id, raw, data) =
+                            reconnecting(&mut dev, &args, serial, |dev| {
+                                let global_id = dev.global_capture_id(title, local_capture_id)?;
+                                let raw = dev.capture_info_raw(global_id)?;
+                                let data = if args.scan_detail {
+                                    Some(dev.capture_data(global_id)?)
+                                } else {
+                                    None
+                                };
+                                Ok((global_id, raw, data))
+                            })?;
+                        if tx.send((global_id, local_capture_id, raw, data)).is_err() {
+                            // the parser gave up, its error is the one to report
+                            break 'scan;
+                        }
+                    }
+                }
+                drop(tx);
+                anyhow::Ok(())
+            })();
+            parser
+                .join()
+                .unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+            read
+        })?;
+        drop(progress);
+        if let Some((cache, path)) = cache.filter(|_| !args.dry_run).zip(cache_path.as_deref()) {
+            if let Err(e) = cache.save(path) {
+                warn!(
+                    "couldn't save the capture cache to {}: {e:#}",
+                    path.display()
+                );
+            }
         }
     }
-}
 
-struct ParseHelper<'a> {
-    remaining: &'a [u8],
-}
+    if let Some(selection) = &args.select {
+        let ids = selection.pick(&cap_infos);
+        cap_infos.retain(|id, _| ids.contains(id));
+    }
 
-impl<'a> ParseHelper<'a> {
-    fn start(to_parse: &'a [u8], name: &str) -> Option<ParseHelper<'a>> {
-        if !to_parse.starts_with(name.as_bytes()) {
-            println!("unpexected start");
-            return None;
+    #[cfg(feature = "sqlite")]
+    if let Some(db) = &args.sqlite {
+        let device_key = match device_key {
+            Some(key) => key,
+            None => dev.device_key()?,
+        };
+        let captures: Vec<_> = cap_infos.iter().map(|(id, (ci, _))| (*id, ci)).collect();
+        sekonic_c_7000::write_sqlite(db, &device_key, &captures)?;
+        println!("wrote {} captures to {}", captures.len(), db.display());
+        dev.end_session()?;
+        return Ok(());
+    }
+
+    if args.log {
+        let path = args.output.unwrap_or_else(|| PathBuf::from("log.csv"));
+        output_dir(&path, args.mkdir)?;
+        log(
+            &mut dev,
+            cap_infos.into_keys().collect(),
+            &path,
+            Duration::from_millis(args.interval_ms),
+            args.duration.map(Duration::from_secs),
+        )?;
+    } else if args.watch {
+        let dir = args.output.clone().unwrap_or_else(|| PathBuf::from("."));
+        watch(
+            &mut dev,
+            &args,
+            &export,
+            cap_infos.into_keys().collect(),
+            &dir,
+            serial,
+        )?;
+    } else if let Some(ids) = &args.compare {
+        let [a, b] = [ids[0], ids[1]].map(|id| cap_infos.get(&id).map(|(ci, _)| ci));
+        let (Some(a), Some(b)) = (a, b) else {
+            bail!("no capture with id {} or {}", ids[0], ids[1]);
+        };
+        print_diff(a, b);
+    } else if let Some(ids) = &args.average {
+        let mut captures = Vec::new();
+        for &global_id in ids {
+            let Some((ci, local_capture_id)) = cap_infos.get(&global_id) else {
+                bail!("no capture with id {global_id}");
+            };
+            let cd = reconnecting(&mut dev, &args, serial, |dev| {
+                Ok(dev.capture_data(global_id)?)
+            })?;
+            captures.push(Capture::new(ci.clone(), cd, *local_capture_id));
         }
+        let viewing_angle = match export.viewing_angle {
+            Some(viewing_angle) => viewing_angle,
+            None => dev.settings().unwrap_or_default().viewing_angle,
+        };
+        let Some(average) = average_captures(&captures, viewing_angle.into()) else {
+            bail!("nothing to average");
+        };
+        println!(
+            "averaged {} captures, CCT standard deviation {:.1} K",
+            average.count, average.cct_std_dev_k
+        );
+        let capture = average.capture;
+        if !args.quiet {
+            print_summary(&capture);
+        }
+        let path = args
+            .output
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("average.{}", args.format.extension())));
+        let (ci, local) = (&capture.info, capture.local_index);
+        export_capture(
+            &mut dev,
+            ids[0],
+            ci,
+            Some(capture.data.clone()),
+            local,
+            &path,
+            &export,
+        )?;
+        if args.plot {
+            write_spectrum_svg(ci, &path.with_extension("svg"))?;
+        }
+        println!("wrote {}", path.display());
+    } else if args.list {
+        // already printed while scanning
+    } else if args.dry_run {
+        let plot = |path: &Path| {
+            if args.plot {
+                println!("would write {}", path.with_extension("svg").display());
+            }
+        };
+        if args.combined {
+            let path = combined_path(&args)?;
+            println!(
+                "would write {} with {} rows",
+                path.display(),
+                cap_infos.len()
+            );
+        } else {
+            let dir = args.output.unwrap_or_else(|| PathBuf::from("."));
+            for path in planned_paths(&cap_infos, &dir, args.format, args.strict, &BTreeMap::new())?
+                .values()
+            {
+                println!("would write {}", path.display());
+                plot(path);
+            }
+        }
+    } else if args.combined {
+        let path = combined_path(&args)?;
+        output_dir(&path, export.mkdir)?;
+        let mut rows = Vec::new();
+        for (global_id, (ci, local_capture_id)) in cap_infos {
+            check_interrupted()?;
+            match reconnecting(&mut dev, &args, serial, |dev| {
+                Ok(dev.capture_data(global_id)?)
+            }) {
+                Ok(cd) => rows.push((global_id, Capture::new(ci, cd, local_capture_id))),
+                Err(e) => println!("failed to export {global_id}: {e:#}"),
+            }
+        }
+        if matches!(args.format, Format::Json) {
+            let captures: Vec<_> = rows.iter().map(|(_, c)| c.clone()).collect();
+            write_all_json(&captures, &dev.metadata(), &path)?;
+        } else {
+            write_combined_csv(&rows, args.spectra, &export.csv, &path)?;
+        }
+        println!("wrote {}", path.display());
+    } else if args.all || args.select.is_some() {
+        export_each(&mut dev, &args, &export, &cap_infos, serial)?;
+    } else {
+        let (global_id, (ci, local_capture_id)) = match args.capture {
+            Some(id) => match cap_infos.get(&id) {
+                Some(ci) => (id, ci),
+                None => bail!("no capture with id {id}"),
+            },
+            // nothing to pick from, the prompt would never accept anything
+            None if cap_infos.is_empty() => {
+                println!("no captures on device");
+                dev.end_session()?;
+                return Ok(());
+            }
+            None => match prompt_capture(&cap_infos)? {
+                Some(ids) if ids.len() > 1 => {
+                    cap_infos.retain(|id, _| ids.contains(id));
+                    export_each(&mut dev, &args, &export, &cap_infos, serial)?;
+                    dev.end_session()?;
+                    return Ok(());
+                }
+                Some(ids) => (ids[0], &cap_infos[&ids[0]]),
+                None => {
+                    dev.end_session()?;
+                    return Ok(());
+                }
+            },
+        };
+        // fetched here for the summary, and handed on so it isn't read twice
+        let data = if args.quiet {
+            None
+        } else {
+            let data = reconnecting(&mut dev, &args, serial, |dev| {
+                Ok(dev.capture_data(global_id)?)
+            })?;
+            let capture = Capture::new(ci.clone(), data, *local_capture_id);
+            print_summary(&capture);
+            Some(capture.data)
+        };
+        let path = match args.output.clone() {
+            Some(path) => path,
+            // keep the old behavior of asking when nothing was given at all
+            None if args.capture.is_none() => {
+                let default = default_filename(ci, *local_capture_id, args.format);
+                match prompt_filename(&default, args.mkdir)? {
+                    Some(path) => path,
+                    None => {
+                        dev.end_session()?;
+                        return Ok(());
+                    }
+                }
+            }
+            None => PathBuf::from(default_filename(ci, *local_capture_id, args.format)),
+        };
+        let local = *local_capture_id;
+        reconnecting(&mut dev, &args, serial, |dev| {
+            export_capture(dev, global_id, ci, data.clone(), local, &path, &export)
+        })?;
+        if args.plot {
+            write_spectrum_svg(ci, &path.with_extension("svg"))?;
+        }
+    }
 
-        // both of these seem to happen. idk if there's rhyme or reason to it
-        let next2 = &to_parse[name.len()..name.len() + 2];
-        if next2 != &b"@@"[..] && next2 != [0x40, 0x20] {
-            return None;
+    dev.end_session()?;
+    Ok(())
+}
+
+type Device = SekonicDevice<Box<dyn Transport>>;
+
+// Each of `cap_infos` into its own file in the output directory, for --all,
+// --select or picking several at the prompt
+fn export_each(
+    dev: &mut Device,
+    args: &Args,
+    export: &ExportOptions,
+    cap_infos: &BTreeMap<u32, (CaptureInfo, u32)>,
+    serial: Option<&str>,
+) -> anyhow::Result<()> {
+    let dir = args.output.clone().unwrap_or_else(|| PathBuf::from("."));
+    let paths = planned_paths(cap_infos, &dir, args.format, args.strict, &BTreeMap::new())?;
+    for (global_id, (ci, local_capture_id)) in cap_infos {
+        let path = &paths[global_id];
+        check_interrupted()?;
+        let local = *local_capture_id;
+        let exported_one = reconnecting(dev, args, serial, |dev| {
+            export_capture(dev, *global_id, ci, None, local, path, export)
+        });
+        match exported_one {
+            Ok(()) => {
+                println!("wrote {}", path.display());
+                if args.plot {
+                    let svg = path.with_extension("svg");
+                    match write_spectrum_svg(ci, &svg) {
+                        Ok(()) => println!("wrote {}", svg.display()),
+                        Err(e) => println!("failed to plot {global_id}: {e:#}"),
+                    }
+                }
+            }
+            Err(e) => println!("failed to export {global_id}: {e:#}"),
         }
+    }
+    Ok(())
+}
 
-        Some(ParseHelper {
-            remaining: &to_parse[name.len() + 2..],
+// The requests this tool sends itself, none of which change anything on the
+// meter. Nobody knows what the others do, they could well delete or overwrite
+fn is_read_only(cmd: &str) -> bool {
+    let numbered = |prefix: &str| {
+        cmd.strip_prefix(prefix).is_some_and(|rest| {
+            !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit() || b == b',')
         })
+    };
+    ["ST", "RT0", "RT1", "MN", "SAr", "FTr", "FV", "IUr", "MI"].contains(&cmd)
+        || ["GT", "GA", "MR", "ME"].into_iter().any(numbered)
+}
+
+// Each request of SekonicDevice::start_session on its own, then MI, so a
+// broken meter or firmware shows up as the step that fails
+fn selftest(dev: &mut Device) -> anyhow::Result<()> {
+    let mut failed = 0;
+    let mut step = |name: &str, res: Result<String, SekonicError>| match res {
+        Ok(s) => println!("{name:<3} ok   {s}"),
+        Err(e) => {
+            println!("{name:<3} FAIL {e:#}");
+            failed += 1;
+        }
+    };
+    // FTr and IUr aren't understood well enough to check what they say, so
+    // any answer at all passes and the text in it is only shown
+    let fields = |f: Vec<&str>| format!("not decoded, text fields {f:?}");
+
+    for req in ["ST", "RT0", "RT1"] {
+        let res = dev.transport().request(req.as_bytes());
+        step(req, res.map(|resp| format!("{} byte response", resp.len())));
     }
+    step("MN", dev.model_info().map(|m| format!("model {}", m.model)));
+    // the settings aren't decoded yet, so there's nothing to show but the size
+    step(
+        "SAr",
+        dev.settings()
+            .map(|s| format!("{} byte payload, not decoded", s.raw().len())),
+    );
+    step("FTr", dev.ft_setting().map(|ft| fields(ft.text_fields())));
+    step(
+        "FV",
+        dev.firmware_version().map(|fv| format!("firmware {fv}")),
+    );
+    step("IUr", dev.iu_info().map(|iu| fields(iu.text_fields())));
+    step(
+        "MI",
+        dev.storage_info()
+            .map(|mi| format!("{} titles, {} captures", mi.num_titles, mi.num_captures)),
+    );
+    step(
+        "ST",
+        dev.end_session().map(|()| "session ended".to_string()),
+    );
 
-    fn bytes(&mut self) -> &'a [u8] {
-        let len = self
-            .remaining
-            .iter()
-            .position(|b| *b == b',')
-            .unwrap_or(self.remaining.len());
-        let ret = &self.remaining[..len];
-        self.remaining = &self.remaining[min(self.remaining.len(), len + 1)..];
-        ret
+    if failed > 0 {
+        bail!("{failed} selftest steps failed");
     }
+    println!("all steps ok");
+    Ok(())
+}
 
-    fn bytes_exact(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
-        if len > self.remaining.len() || (len < self.remaining.len() && self.remaining[len] != b',')
-        {
-            bail!("did not find a ',' in the right distance")
-        }
-        let ret = &self.remaining[..len];
-        self.remaining = &self.remaining[min(self.remaining.len(), len + 1)..];
-        Ok(ret)
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+// dropping the device on the way out of main releases the interface
+fn check_interrupted() -> anyhow::Result<()> {
+    if interrupted() {
+        bail!("interrupted");
     }
+    Ok(())
+}
 
-    fn unsigned(&mut self) -> Option<u32> {
-        str::from_utf8(self.bytes()).ok()?.parse().ok()
+// `serial` rather than --serial, so a reconnect can ask for the meter it had
+fn open(args: &Args, serial: Option<&str>) -> anyhow::Result<Device> {
+    if let Some(dir) = &args.replay {
+        return Ok(SekonicDevice::new(Box::new(MockTransport::from_dir(dir)?)));
     }
 
-    fn string(&mut self) -> Option<String> {
-        let str = str::from_utf8(self.bytes()).ok()?;
-        Some(
-            if let Some(idx) = str.find('\0') {
-                &str[..idx]
-            } else {
-                str
+    let ctx = rusb::Context::new()?;
+    let mut builder = SekonicDevice::builder()
+        .timeout(Duration::from_millis(args.timeout_ms))
+        .retries(args.attempts.saturating_sub(1));
+    if let Some(serial) = serial {
+        builder = builder.serial(serial);
+    }
+    let usb = builder.build(&ctx)?.into_transport();
+
+    Ok(SekonicDevice::new(match &args.record {
+        Some(dir) => Box::new(RecordingTransport::new(usb, dir)?),
+        None => Box::new(usb),
+    }))
+}
+
+// where --combined writes, checking it's a format that can hold more than one
+fn combined_path(args: &Args) -> anyhow::Result<PathBuf> {
+    if !matches!(args.format, Format::Csv | Format::Json) {
+        bail!("--combined only writes csv or json");
+    }
+    Ok(args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("captures.{}", args.format.extension()))))
+}
+
+// The ids picked, at least one and all of them in `cap_infos`. None if the
+// user quit instead of picking
+fn prompt_capture(
+    cap_infos: &BTreeMap<u32, (CaptureInfo, u32)>,
+) -> anyhow::Result<Option<Vec<u32>>> {
+    println!("select a number to dump, or several like 3,5,7-10, or q to quit");
+    loop {
+        let Some(answer) = read_answer()? else {
+            return Ok(None);
+        };
+        if answer.is_empty() {
+            continue;
+        }
+        match parse_selection(&answer) {
+            Ok(selection) => {
+                let ids = selection.pick(cap_infos);
+                if !ids.is_empty() {
+                    return Ok(Some(ids));
+                }
+            }
+            Err(e) => println!("{e}, enter a number or a list like 3,5,7-10"),
+        }
+    }
+}
+
+// None if the user quit. A blank answer takes `default`, existing files are
+// only overwritten once confirmed, and a missing directory is only created
+// once confirmed unless `mkdir`
+fn prompt_filename(default: &str, mkdir: bool) -> anyhow::Result<Option<PathBuf>> {
+    loop {
+        println!("enter filename [{default}], or q to quit: ");
+        let Some(answer) = read_answer()? else {
+            return Ok(None);
+        };
+        let path = if answer.is_empty() {
+            PathBuf::from(default)
+        } else {
+            let path = expand_home(&answer);
+            match path.file_name().and_then(|n| n.to_str()) {
+                Some(name) => path.with_file_name(sanitize_filename(name)),
+                None => {
+                    println!("{} isn't a file name", path.display());
+                    continue;
+                }
             }
-            .to_owned(),
-        )
+        };
+        let dir = path.parent().filter(|d| !d.as_os_str().is_empty());
+        if let Some(dir) = dir.filter(|d| !d.is_dir()) {
+            if !mkdir {
+                println!("{} doesn't exist, create it? [y/N]", dir.display());
+                match read_answer()? {
+                    Some(answer) if answer.eq_ignore_ascii_case("y") => {}
+                    Some(_) => continue,
+                    None => return Ok(None),
+                }
+            }
+            if let Err(e) = make_parent(&path) {
+                println!("{e:#}");
+                continue;
+            }
+        }
+        if !path.exists() {
+            return Ok(Some(path));
+        }
+        println!("{} already exists, overwrite? [y/N]", path.display());
+        match read_answer()? {
+            Some(answer) if answer.eq_ignore_ascii_case("y") => return Ok(Some(path)),
+            Some(_) => {}
+            None => return Ok(None),
+        }
     }
+}
 
-    fn float(&mut self) -> anyhow::Result<f32> {
-        let b = self.bytes_exact(4)?;
-        Ok(f32::from_be_bytes(b.try_into().map_err(|e| {
-            format_err!("wrong length, expected 4 got {}", b.len())
-        })?))
+// A trimmed line from stdin, or None on EOF, q or quit
+fn read_answer() -> anyhow::Result<Option<String>> {
+    let mut line = String::new();
+    let read = stdin().read_line(&mut line)?;
+    check_interrupted()?;
+    let answer = line.trim();
+    if read == 0 || answer == "q" || answer == "quit" {
+        return Ok(None);
     }
+    Ok(Some(answer.to_owned()))
+}
 
-    fn double(&mut self) -> anyhow::Result<f64> {
-        let b = self.bytes_exact(8)?;
-        Ok(f64::from_be_bytes(b.try_into().map_err(|e| {
-            format_err!("wrong length, expected 8 got {}", b.len())
-        })?))
+// Measure every `interval` and append what comes back to `path`. Without
+// remote triggering this settles for logging whatever captures were taken on
+// the meter since the last poll.
+fn log(
+    dev: &mut Device,
+    mut seen: BTreeSet<u32>,
+    path: &Path,
+    interval: Duration,
+    duration: Option<Duration>,
+) -> anyhow::Result<()> {
+    let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+    if f.metadata()?.len() == 0 {
+        writeln!(
+            f,
+            "Time,Global Index,Title,CCT [K],⊿uv,Illuminance [lx],Illuminance [fc],\
+             CIE1931 x,CIE1931 y,CIE1976 u',CIE1976 v'"
+        )?;
     }
 
-    fn collect_remaining(&mut self) -> Vec<HVec> {
-        let mut ret = vec![];
-        loop {
-            let b = self.bytes();
-            if b.len() == 0 {
-                return ret;
+    let mut can_trigger = true;
+    let start = Instant::now();
+    while !interrupted() && duration.is_none_or(|d| start.elapsed() < d) {
+        let next = Instant::now() + interval;
+
+        if can_trigger {
+            if let Err(e) = dev.trigger_measurement() {
+                if let SekonicError::Unsupported(_) = e {
+                    println!("{e}, logging captures taken on the meter instead");
+                    can_trigger = false;
+                } else {
+                    println!("measurement failed, skipping: {e:#}");
+                }
             }
+        }
 
-            ret.push(b.to_owned().into())
+        // the meter can be busy measuring, in which case try again next time
+        match new_captures(dev, &seen) {
+            Ok(new) => {
+                for (global_id, (ci, _)) in new {
+                    writeln!(
+                        f,
+                        "{},{},{},{},{},{},{},{},{},{},{}",
+                        chrono::offset::Local::now().to_rfc3339(),
+                        global_id,
+                        csv_escape(&ci.title),
+                        Precision::Whole.format(ci.cct_k),
+                        Precision::Chromaticity.format(ci.uv_angle),
+                        Precision::Whole.format(ci.illum_lx),
+                        Precision::Tenths.format(ci.illum_fc),
+                        Precision::Chromaticity.format(ci.cie1931_x),
+                        Precision::Chromaticity.format(ci.cie1931_y),
+                        Precision::Chromaticity.format(ci.cie1976_up),
+                        Precision::Chromaticity.format(ci.cie1976_vp)
+                    )?;
+                    println!("{global_id:2}: {} {}", ci.title, ci.cct_k);
+                    seen.insert(global_id);
+                }
+            }
+            Err(e) => println!("failed to read from the meter, skipping: {e:#}"),
         }
-    }
 
-    fn float_array<const LEN: usize>(&mut self) -> anyhow::Result<[f32; LEN]> {
-        let b = self.bytes_exact(4 * LEN)?;
-        Ok(array::from_fn(|i| {
-            f32::from_be_bytes([b[i * 4 + 0], b[i * 4 + 1], b[i * 4 + 2], b[i * 4 + 3]])
-        }))
+        // sleep in small steps so Ctrl-C doesn't wait out a long interval
+        while !interrupted() && Instant::now() < next {
+            thread::sleep(min(next - Instant::now(), Duration::from_millis(100)));
+        }
     }
+
+    f.flush()?;
+    println!("wrote {}", path.display());
+    Ok(())
 }
 
-// "MIB" structure
-#[derive(Debug)]
-struct StorageInfoResp {
-    _unk1: u32,
-    num_captures: u32,
-    num_titles: u32,
+// keyed by global id like the scan, with the local index
+fn new_captures(
+    dev: &mut Device,
+    seen: &BTreeSet<u32>,
+) -> anyhow::Result<BTreeMap<u32, (CaptureInfo, u32)>> {
+    let mut ret = BTreeMap::new();
+    let info = dev.storage_info()?;
+    if info.num_captures as usize == seen.len() {
+        return Ok(ret);
+    }
+    for title in 1..=info.num_titles {
+        for local_capture_id in 1..=dev.title_info(title)?.num_captures {
+            let global_id = dev.global_capture_id(title, local_capture_id)?;
+            if !seen.contains(&global_id) {
+                ret.insert(global_id, (dev.capture_info(global_id)?, local_capture_id));
+            }
+        }
+    }
+    Ok(ret)
 }
 
-impl StorageInfoResp {
-    fn parse(i: &[u8]) -> StorageInfoResp {
-        let mut p = ParseHelper::start(i, "MIB").unwrap();
-        StorageInfoResp {
-            _unk1: p.unsigned().unwrap(),
-            num_captures: p.unsigned().unwrap(),
-            num_titles: p.unsigned().unwrap(),
+// Export every capture that shows up on the meter into `dir`, checking every
+// --interval-ms. `seen` are the ones already there, which are left alone.
+fn watch(
+    dev: &mut Device,
+    args: &Args,
+    export: &ExportOptions,
+    mut seen: BTreeSet<u32>,
+    dir: &Path,
+    serial: Option<&str>,
+) -> anyhow::Result<()> {
+    let interval = Duration::from_millis(args.interval_ms);
+    // what earlier polls wrote, so a new capture with the same name doesn't
+    // overwrite one of them
+    let mut written = BTreeMap::new();
+    println!("watching for new captures, Ctrl-C to stop");
+    while !interrupted() {
+        let next = Instant::now() + interval;
+
+        let mut unplugged = false;
+        match new_captures(dev, &seen) {
+            Ok(new) => {
+                let paths = planned_paths(&new, dir, args.format, args.strict, &written)?;
+                for (global_id, (ci, local_capture_id)) in &new {
+                    let path = &paths[global_id];
+                    let local = *local_capture_id;
+                    match export_capture(dev, *global_id, ci, None, local, path, export) {
+                        Ok(()) => {
+                            println!("wrote {}", path.display());
+                            written.insert(path.clone(), *global_id);
+                        }
+                        // not seen yet, so it's exported once the meter is back
+                        Err(e) if is_unplugged(&e) => {
+                            unplugged = true;
+                            break;
+                        }
+                        Err(e) => println!("failed to export {global_id}: {e:#}"),
+                    }
+                    seen.insert(*global_id);
+                }
+            }
+            Err(e) if is_unplugged(&e) => unplugged = true,
+            // the meter can be busy measuring, in which case try again next time
+            Err(e) => println!("failed to read from the meter, skipping: {e:#}"),
+        }
+        if unplugged {
+            println!("meter unplugged, waiting for it to come back");
+            *dev = reopen(args, serial)?;
+            println!("meter is back");
+            continue;
+        }
+
+        // sleep in small steps so Ctrl-C doesn't wait out a long interval
+        while !interrupted() && Instant::now() < next {
+            thread::sleep(min(next - Instant::now(), Duration::from_millis(100)));
         }
     }
+    Ok(())
 }
 
-fn get_storage_info(d: &mut DeviceHandle) -> StorageInfoResp {
-    StorageInfoResp::parse(&make_req(d, b"MI"))
+fn is_unplugged(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref(),
+        Some(SekonicError::Usb(rusb::Error::NoDevice))
+    )
 }
 
-// "GTB" structure
-#[derive(Debug)]
-struct TitleInfo {
-    name: String,
-    num_captures: u32,
+// open the meter and start a session, retrying every second until it's there
+fn reopen(args: &Args, serial: Option<&str>) -> anyhow::Result<Device> {
+    loop {
+        check_interrupted()?;
+        if let Ok(mut dev) = open(args, serial) {
+            if dev.start_session().is_ok() {
+                return Ok(dev);
+            }
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
 }
 
-impl TitleInfo {
-    fn parse(i: &[u8]) -> TitleInfo {
-        let mut p = ParseHelper::start(i, "GTB").unwrap();
-        TitleInfo {
-            name: p.string().unwrap(),
-            num_captures: p.unsigned().unwrap(),
+// Run `step`, and if the meter was unplugged while it ran, open it again and
+// run it again, up to --reconnects times. `serial` makes sure it's the same
+// meter. Steps only read from the meter, so repeating one is harmless
+fn reconnecting<T>(
+    dev: &mut Device,
+    args: &Args,
+    serial: Option<&str>,
+    mut step: impl FnMut(&mut Device) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let mut reconnects = 0;
+    loop {
+        match step(dev) {
+            Err(e) if is_unplugged(&e) && reconnects < args.reconnects => {
+                reconnects += 1;
+                println!(
+                    "meter unplugged, reconnecting ({reconnects} of {})",
+                    args.reconnects
+                );
+                thread::sleep(Duration::from_millis(args.reconnect_delay_ms));
+                check_interrupted()?;
+                let reopened = open(args, serial).and_then(|mut dev| {
+                    dev.start_session()?;
+                    Ok(dev)
+                });
+                match reopened {
+                    Ok(reopened) => *dev = reopened,
+                    // still the unplugged one, so the next try fails the same way
+                    Err(e) => println!("couldn't open the meter again: {e:#}"),
+                }
+            }
+            res => return res,
         }
     }
 }
 
-// 1 indexed
-fn get_title_info(d: &mut DeviceHandle, id: u32) -> TitleInfo {
-    assert!(id > 0);
-    TitleInfo::parse(&make_req(d, format!("GT{id:04}").as_bytes()))
+fn print_diff(a: &CaptureInfo, b: &CaptureInfo) {
+    let fmt = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{v:.4}"));
+    println!("{:<18} {:>14} {:>14} {:>14}", "metric", "a", "b", "delta");
+    for diff in diff_captures(a, b) {
+        println!(
+            "{:<18} {:>14} {:>14} {:>14.4}",
+            diff.name,
+            fmt(diff.a),
+            fmt(diff.b),
+            diff.delta
+        );
+    }
 }
 
-// 1 indexed
-fn get_global_capture_id(d: &mut DeviceHandle, title_id: u32, local_capture_id: u32) -> u32 {
-    assert!(title_id > 0);
-    assert!(local_capture_id > 0);
+fn parse_output(s: &str) -> Result<PathBuf, String> {
+    Ok(expand_home(s))
+}
 
-    ParseHelper::start(
-        &make_req(
-            d,
-            format!("GA{title_id:04},{local_capture_id:04}").as_bytes(),
-        ),
-        "GAB",
-    )
-    .unwrap()
-    .unsigned()
-    .unwrap()
-}
-
-// "MRB" structure
-#[derive(Debug)]
-struct CaptureInfo {
-    unk0: u32,
-    title: String, // NOTE: not title of capture, title of "title", lol
-    unk1: u32,     // 6
-    unk2: u32,     // 0
-    unk3: u32,     // 00
-    unk4: u32,     // 0
-    unk5: HVec,    // all null
-    unk6: u32,     // 0
-    unk7: HVec,    // all null
-    unk8: u32,     // 0
-    cct_k: f32,
-    uv_angle: f32, // unsure what to call this lol. output has "⊿uv"
-    unk11: u32,    // 0
-    unks: [HVec; 6],
-    illum_lx: f32,
-    illum_fc: f32,
-    tristimulus_x: f64,
-    tristimulus_y: f64,
-    tristimulus_z: f64,
-    cie1931_x: f32,
-    cie1931_y: f32,
-    // cie1931_z: f32, ?????
-    cie1976_up: f32,
-    unk12: f32,
-    unk13: f32,
-    cie1976_vp: f32,
-    dominant_wavelength: f32,
-    purity: f32,
-    // ppfd: f32,
-    cri_ra: f32,
-    cri: [f32; 15],
-
-    // 5nm steps starting at 380nm
-    spectral_data_5nm: [f32; 81],
-
-    // 1nm steps starting at 380nm
-    spectral_data_1nm: [f32; 401],
-    unk14: [u32; 4],
-    unk15: [f32; 2],
-    ppfd: f32,
-
-    // tm_30_rf: f32,
-    // tm_30_rg: f32,
-    // ssit: f32,
-    // ssid: f32,
-    // ssi1: f32,
-    // ssi2: f32,
-    // tlci: f32,
-    // tlmf: f32,
-    // and so many more...
-    remaining: Vec<HVec>,
-}
-
-impl CaptureInfo {
-    fn parse(i: &[u8]) -> CaptureInfo {
-        let mut p = ParseHelper::start(i, "MRB").unwrap();
-        CaptureInfo {
-            unk0: p.unsigned().unwrap(),
-            title: p.string().unwrap(),
-            unk1: p.unsigned().unwrap(),
-            unk2: p.unsigned().unwrap(),
-            unk3: p.unsigned().unwrap(),
-            unk4: p.unsigned().unwrap(),
-            unk5: p.bytes().to_owned().into(),
-            unk6: p.unsigned().unwrap(),
-            unk7: p.bytes().to_owned().into(),
-            unk8: p.unsigned().unwrap(),
-            cct_k: p.float().unwrap(),
-            uv_angle: p.float().unwrap(),
-            unk11: p.unsigned().unwrap(),
-            unks: array::from_fn(|_| p.bytes().to_owned().into()),
-            illum_lx: p.float().unwrap(),
-            illum_fc: p.float().unwrap(),
-            tristimulus_x: p.double().unwrap(),
-            tristimulus_y: p.double().unwrap(),
-            tristimulus_z: p.double().unwrap(),
-            cie1931_x: p.float().unwrap(),
-            cie1931_y: p.float().unwrap(),
-            // cie1931_z: p.float().unwrap(),
-            cie1976_up: p.float().unwrap(),
-            unk12: p.float().unwrap(),
-            unk13: p.float().unwrap(),
-            cie1976_vp: p.float().unwrap(),
-            dominant_wavelength: p.float().unwrap(),
-            purity: p.float().unwrap(),
-            // ppfd: p.float().unwrap(),
-            cri_ra: p.float().unwrap(),
-            cri: array::from_fn(|_| p.float().unwrap()),
-            spectral_data_5nm: p.float_array().unwrap(),
-            spectral_data_1nm: p.float_array().unwrap(),
-            // tm_30_rf: p.float().unwrap(),
-            // tm_30_rg: p.float().unwrap(),
-            // ssit: p.float().unwrap(),
-            // ssid: p.float().unwrap(),
-            // ssi1: p.float().unwrap(),
-            // ssi2: p.float().unwrap(),
-            // tlci: p.float().unwrap(),
-            // tlmf: p.float().unwrap(),
-            unk14: array::from_fn(|_| p.unsigned().unwrap()),
-            unk15: array::from_fn(|_| p.float().unwrap()),
-            ppfd: p.float().unwrap(),
-            remaining: p.collect_remaining(),
-        }
-    }
-}
-
-fn get_capture_info(d: &mut DeviceHandle, global_capture_id: u32) -> CaptureInfo {
-    CaptureInfo::parse(&make_req(d, format!("MR{global_capture_id:04}").as_bytes()))
-}
-
-// Probably need to name this better, oh well
-// "MEB" structure
-#[derive(Debug)]
-struct CaptureData {
-    tm_30_rf: f32,
-    tm_30_rg: f32,
-    illuminants: [[f32; 4]; 16],
-    ssit: f32,
-    ssid: f32,
-    unk3: u32,
-    unk4: f32,
-    unk5: u32,
-    unk6: f32,
-    tlci: f32,
-    unk8: u32,
-    unk9: [f32; 3],
-    unk10: u32,
-    unk11: u32,
-    // unk2: [f32; 10],
-    // remaining: HVec,
-}
-
-impl CaptureData {
-    fn parse(i: &[u8]) -> CaptureData {
-        let mut p = ParseHelper::start(i, "MEB").unwrap();
-        let tm_30_rf = p.float().unwrap();
-        let tm_30_rg = p.float().unwrap();
-        let mut illuminants = [[0.; 4]; 16];
-        for row in 0..16 {
-            for col in 0..4 {
-                illuminants[row][col] = p.float().unwrap();
-            }
-        }
-        // let mut unk2 = [0.; 10];
-        // for u in &mut unk2 {
-        //     *u = p.float().unwrap();
-        // }
-        CaptureData {
-            tm_30_rf,
-            tm_30_rg,
-            illuminants,
-            ssit: p.float().unwrap(),
-            ssid: p.float().unwrap(),
-            unk3: p.unsigned().unwrap(),
-            unk4: p.float().unwrap(),
-            unk5: p.unsigned().unwrap(),
-            unk6: p.float().unwrap(),
-            tlci: p.float().unwrap(),
-            unk8: p.unsigned().unwrap(),
-            unk9: array::from_fn(|_| p.float().unwrap()),
-            unk10: p.unsigned().unwrap(),
-            unk11: p.unsigned().unwrap(),
-            // remaining: p.remaining.to_owned().into(),
-        }
-    }
-}
-
-fn get_capture_data(d: &mut DeviceHandle, global_capture_id: u32) -> CaptureData {
-    CaptureData::parse(&make_req(d, format!("ME{global_capture_id:04}").as_bytes()))
-}
-
-fn write_csv(cd: &CaptureData, ci: &CaptureInfo, local_capture_idx: u32, path: &Path) {
-    let mut f = File::create(path).unwrap();
-    writeln!(
-        &mut f,
-        "Date Saved,{}",
-        chrono::offset::Local::now().format("%Y/%m/%d %H:%M:%S")
-    )
-    .unwrap();
-    writeln!(
-        &mut f,
-        "Title,{}_{:03}_{:02}°_{:.0}K\n",
-        ci.title, local_capture_idx, 2, ci.cct_k
-    )
-    .unwrap(); // TODO: angle
-               // writeln!(&mut f, "Measuring Mode,{}", 999).unwrap(); // TODO:
-               // writeln!(&mut f, "Viewing Angle,{}", 999).unwrap(); // TODO:
-    writeln!(&mut f, "CCT [K],{:.0}", ci.cct_k).unwrap();
-    writeln!(&mut f, "⊿uv,{:.4}", ci.uv_angle).unwrap();
-    writeln!(&mut f, "Illuminance [lx],{:.0}", ci.illum_lx).unwrap();
-    writeln!(&mut f, "Illuminance [fc],{:.1}", ci.illum_fc).unwrap();
-    writeln!(
-        &mut f,
-        "Peak Wavelength [nm],{}",
-        ci.spectral_data_1nm
-            .iter()
-            .enumerate()
-            .max_by(|(_, a), (_, b)| a.total_cmp(b))
-            .unwrap()
-            .0
-            + 380
-    )
-    .unwrap(); // TODO
-    writeln!(&mut f, "Tristimulus Value X,{:.4}", ci.tristimulus_x).unwrap();
-    writeln!(&mut f, "Tristimulus Value Y,{:.4}", ci.tristimulus_y).unwrap();
-    writeln!(&mut f, "Tristimulus Value Z,{:.4}", ci.tristimulus_z).unwrap();
-    writeln!(&mut f, "CIE1931 x,{:.4}", ci.cie1931_x).unwrap();
-    writeln!(&mut f, "CIE1931 y,{:.4}", ci.cie1931_y).unwrap();
-    writeln!(&mut f, "CIE1931 z,{:.4}", 1. - ci.cie1931_x - ci.cie1931_y).unwrap();
-    writeln!(&mut f, "CIE1976 u',{:.4}", ci.cie1976_up).unwrap();
-    writeln!(&mut f, "CIE1976 v',{:.4}", ci.cie1976_vp).unwrap();
-    writeln!(
-        &mut f,
-        "Dominant Wavelength [nm],{:.0}",
-        ci.dominant_wavelength
-    )
-    .unwrap();
-    writeln!(&mut f, "Purity [%],{:.1}", ci.purity).unwrap();
-    writeln!(&mut f, "PPFD [umolm⁻²s⁻¹],{:.1}", ci.ppfd).unwrap();
-    writeln!(&mut f, "CRI Ra,{:.1}", ci.cri_ra).unwrap();
-    for (i, val) in ci.cri.iter().enumerate() {
-        writeln!(&mut f, "CRI R{},{:.1}", i + 1, val).unwrap();
-    }
-    writeln!(&mut f, "TM-30 Rf,{:.0}", cd.tm_30_rf).unwrap();
-    writeln!(&mut f, "TM-30 Rg,{:.0}", cd.tm_30_rg).unwrap();
-    writeln!(&mut f, "SSIt,{:.0}", cd.ssit).unwrap();
-    writeln!(&mut f, "SSId,{:.0}", cd.ssid).unwrap();
-    writeln!(&mut f, "TLCI,{:.0}", cd.tlci).unwrap();
-    // TODO: a few fields belong here
-    writeln!(&mut f, "").unwrap();
-    for (i, val) in ci.spectral_data_5nm.iter().enumerate() {
-        writeln!(&mut f, "Spectral Data {}[nm],{:.12}", 380 + i * 5, val).unwrap();
-    }
-    writeln!(&mut f, "").unwrap();
-    for (i, val) in ci.spectral_data_1nm.iter().enumerate() {
-        writeln!(&mut f, "Spectral Data {}[nm],{:.12}", 380 + i, val).unwrap();
-    }
-    writeln!(&mut f, "").unwrap();
-    writeln!(&mut f, "TM-30 Color Vector Graphic,Reference Illuminant x,Reference Illuminant y,Measured Illuminant x,Measured Illuminant y").unwrap();
-    for (i, val) in cd.illuminants.iter().enumerate() {
-        writeln!(
-            &mut f,
-            "bin{},{:.7},{:.7},{:.7},{:.7}",
-            i + 1,
-            val[0],
-            val[1],
-            val[2],
-            val[3]
-        )
-        .unwrap();
-    }
-}
-
-fn main() {
-    let ctx = libusb::Context::new().unwrap();
-    let devs = ctx.devices().unwrap();
-
-    let d = devs
-        .iter()
-        .find(|d| {
-            let desc = d.device_descriptor().unwrap();
-            desc.vendor_id() == VENDOR_ID && desc.product_id() == PRODUCT_ID
-        })
-        .expect("No sekonic 7000 dectected");
+fn parse_csv_style(s: &str) -> Result<CsvStyle, String> {
+    match s {
+        "sekonic" => Ok(CsvStyle::Sekonic),
+        "normalized" => Ok(CsvStyle::Normalized),
+        _ => Err("expected sekonic or normalized".to_string()),
+    }
+}
 
-    let desc = d.device_descriptor().unwrap();
-    let mut h = d.open().unwrap();
+fn parse_spectrum_layout(s: &str) -> Result<SpectrumLayout, String> {
+    match s {
+        "rows" => Ok(SpectrumLayout::Rows),
+        "columns" => Ok(SpectrumLayout::Columns),
+        _ => Err("expected rows or columns".to_string()),
+    }
+}
 
-    let mut out_endpoint = None;
-    let mut in_endpoint = None;
-    'outer: for n in 0..desc.num_configurations() {
-        let config_desc = match d.config_descriptor(n) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
+// Capture ids and inclusive ranges of them, from --select or the prompt
+#[derive(Debug, Clone)]
+struct Selection(Vec<RangeInclusive<u32>>);
 
-        for interface in config_desc.interfaces() {
-            for interface_desc in interface.descriptors() {
-                out_endpoint = None;
-                in_endpoint = None;
-                for endpoint_desc in interface_desc.endpoint_descriptors() {
-                    if endpoint_desc.direction() == libusb::Direction::Out
-                        && endpoint_desc.transfer_type() == TransferType::Bulk
-                    {
-                        println!(
-                            "found OUT endpoint number={} config={} iface={} setting={} address={}",
-                            endpoint_desc.number(),
-                            config_desc.number(),
-                            interface_desc.interface_number(),
-                            interface_desc.setting_number(),
-                            endpoint_desc.address()
-                        );
-                        out_endpoint = Some(endpoint_desc.address());
-                    }
-                    if endpoint_desc.direction() == libusb::Direction::In
-                        && endpoint_desc.transfer_type() == TransferType::Bulk
-                    {
-                        println!(
-                            "found IN endpoint number={} config={} iface={} setting={} address={}",
-                            endpoint_desc.number(),
-                            config_desc.number(),
-                            interface_desc.interface_number(),
-                            interface_desc.setting_number(),
-                            endpoint_desc.address()
-                        );
-                        in_endpoint = Some(endpoint_desc.address());
-                    }
+impl Selection {
+    // The selected ids that are in `cap_infos`, in order. Ids and ranges
+    // that match none of them are reported, a range with only some of its ids
+    // there isn't since deleted captures leave gaps
+    fn pick(&self, cap_infos: &BTreeMap<u32, (CaptureInfo, u32)>) -> Vec<u32> {
+        let mut ret = BTreeSet::new();
+        for range in &self.0 {
+            let mut found = cap_infos.range(range.clone()).map(|(id, _)| *id).peekable();
+            if found.peek().is_none() {
+                if range.start() == range.end() {
+                    println!("no capture with id {}", range.start());
+                } else {
+                    println!("no captures in {}-{}", range.start(), range.end());
                 }
-                if let (Some(out), Some(i)) = (out_endpoint, in_endpoint) {
-                    h.set_active_configuration(config_desc.number()).unwrap();
-                    h.claim_interface(interface_desc.interface_number())
-                        .unwrap();
-                    // h.set_alternate_setting(interface_desc.interface_number(), interface_desc.setting_number()).unwrap();
+            }
+            ret.extend(found);
+        }
+        ret.into_iter().collect()
+    }
+}
 
-                    break 'outer;
+fn parse_selection(s: &str) -> Result<Selection, String> {
+    let id = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("{:?} isn't a capture id", s.trim()))
+    };
+    s.split(',')
+        .map(|part| match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (id(start)?, id(end)?);
+                if start > end {
+                    return Err(format!("{} is backwards", part.trim()));
                 }
+                Ok(start..=end)
             }
+            None => id(part).map(|id| id..=id),
+        })
+        .collect::<Result<_, _>>()
+        .map(Selection)
+}
+
+fn parse_fields(s: &str) -> Result<FieldSelection, String> {
+    FieldSelection::parse(s).map_err(|e| e.to_string())
+}
+
+fn parse_viewing_angle(s: &str) -> Result<ViewingAngle, String> {
+    s.parse()
+        .ok()
+        .and_then(ViewingAngle::from_degrees)
+        .ok_or_else(|| "expected 2 or 10".to_string())
+}
+
+// Where --all writes each capture in `dir`, by global id. Captures that would
+// end up with the same name, as each other or as a capture in `written` (path
+// to global id) from before, get their global id added to it instead, or with
+// `strict` it's an error. The ones in `written` keep their names.
+fn planned_paths(
+    cap_infos: &BTreeMap<u32, (CaptureInfo, u32)>,
+    dir: &Path,
+    format: Format,
+    strict: bool,
+    written: &BTreeMap<PathBuf, u32>,
+) -> anyhow::Result<BTreeMap<u32, PathBuf>> {
+    let mut by_path: BTreeMap<PathBuf, Vec<u32>> = BTreeMap::new();
+    for (global_id, (ci, local_capture_id)) in cap_infos {
+        let path = dir.join(default_filename(ci, *local_capture_id, format));
+        by_path.entry(path).or_default().push(*global_id);
+    }
+
+    let mut ret = BTreeMap::new();
+    for (path, ids) in by_path {
+        let earlier = written.get(&path);
+        if let ([global_id], None) = (&ids[..], earlier) {
+            ret.insert(*global_id, path);
+            continue;
+        }
+        let ids_str: Vec<_> = earlier
+            .map(|id| format!("{id} (already written)"))
+            .into_iter()
+            .chain(ids.iter().map(u32::to_string))
+            .collect();
+        let msg = format!(
+            "captures {} would all be written to {}",
+            ids_str.join(", "),
+            path.display()
+        );
+        if strict {
+            bail!(msg);
+        }
+        warn!("{msg}, adding the global id to their names");
+        for global_id in ids {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let name = format!("{stem}_{global_id}.{}", format.extension());
+            ret.insert(global_id, path.with_file_name(name));
         }
     }
+    Ok(ret)
+}
 
-    // not entirely sure what these do, but do them for consistency
-    make_req(&mut h, b"ST");
-    make_req(&mut h, b"RT0");
-    make_req(&mut h, b"RT1");
-    make_req(&mut h, b"MN");
-    make_req(&mut h, b"SAr");
-    make_req(&mut h, b"FTr");
-    make_req(&mut h, b"FV");
-    make_req(&mut h, b"IUr");
+// same naming as the desktop software, e.g. TITLE1_003_3200K.csv
+fn default_filename(ci: &CaptureInfo, local_capture_id: u32, format: Format) -> String {
+    sanitize_filename(&format!(
+        "{}_{:03}_{:.0}K.{}",
+        ci.title,
+        local_capture_id,
+        ci.cct_k,
+        format.extension()
+    ))
+}
 
-    let mut cap_infos = BTreeMap::new();
-    let info = get_storage_info(&mut h);
-    for title in 1..=info.num_titles {
-        let title_info = get_title_info(&mut h, title);
-        for local_capture_id in 1..=title_info.num_captures {
-            let global_id = get_global_capture_id(&mut h, title, local_capture_id);
-            let cap_info = get_capture_info(&mut h, global_id);
-            println!(
-                "{:2}: {} {} {}",
-                global_id, cap_info.title, local_capture_id, cap_info.cct_k
-            );
-            cap_infos.insert(global_id, (cap_info, local_capture_id));
+// `~` or `~/...` from the home directory. Anything else, like `~user`, is
+// left alone
+fn expand_home(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) if rest.starts_with(['/', std::path::MAIN_SEPARATOR]) => &rest[1..],
+        _ => return PathBuf::from(path),
+    };
+    match std::env::var_os("HOME").filter(|h| !h.is_empty()) {
+        Some(home) => Path::new(&home).join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+// The directory `path` is in, and any above it that are missing
+fn make_parent(path: &Path) -> anyhow::Result<()> {
+    match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) => fs::create_dir_all(dir)
+            .with_context(|| format!("couldn't create the directory {}", dir.display())),
+        None => Ok(()),
+    }
+}
+
+// Make sure there's a directory to write `path` into, creating it with
+// --mkdir, so a typo fails with more than "No such file or directory"
+fn output_dir(path: &Path, mkdir: bool) -> anyhow::Result<()> {
+    if mkdir {
+        return make_parent(path);
+    }
+    match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) if !dir.is_dir() => {
+            bail!("{} doesn't exist, pass --mkdir to create it", dir.display())
         }
+        _ => Ok(()),
     }
+}
 
-    println!("select a number to dump");
-    let mut line = String::new();
-    let (global_id, (ci, local_capture_id)) = loop {
-        stdin().read_line(&mut line).unwrap();
-        match line.trim().parse() {
-            Ok(i) => match cap_infos.get(&i) {
-                Some(ci) => break (i, ci),
-                None => println!("{i} was not a valid choice"),
-            },
-            Err(_) => println!("enter a number"),
+// Replace anything that's not safe in a file name on common filesystems.
+// Titles are typed on the meter, so they can contain anything.
+fn sanitize_filename(name: &str) -> String {
+    let ret: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match ret.trim() {
+        "" | "." | ".." => "capture".into(),
+        _ => ret,
+    }
+}
+
+// `data` is there with --scan-detail
+fn print_capture(
+    global_id: u32,
+    ci: &CaptureInfo,
+    local_capture_id: u32,
+    data: Option<&CaptureData>,
+    swatch: bool,
+) {
+    print!(
+        "{:2}: {} {} {}",
+        global_id, ci.title, local_capture_id, ci.cct_k
+    );
+    if let Some(cd) = data {
+        print!(
+            " Ra {:.1} Rf {:.0} Rg {:.0}",
+            ci.cri_ra, cd.tm_30_rf, cd.tm_30_rg
+        );
+    }
+    if swatch {
+        match ci.srgb() {
+            Some([r, g, b]) => print!(" #{r:02x}{g:02x}{b:02x}"),
+            None => print!(" dark"),
         }
-    };
-    println!("enter filename: ");
-    line.clear();
-    stdin().read_line(&mut line).unwrap();
-    write_csv(
-        &get_capture_data(&mut h, global_id),
-        ci,
-        *local_capture_id,
-        Path::new(&line.trim()),
+    }
+    println!();
+}
+
+// The main numbers of the capture about to be exported, rounded like the CSV
+fn print_summary(capture: &Capture) {
+    let (ci, cd) = (&capture.info, &capture.data);
+    println!("{}, capture {}", capture.title, capture.local_index);
+    println!("  illuminance {:.0} lx", ci.illuminance_lux());
+    if ci.is_dark() {
+        println!("  too dark for the color metrics");
+        return;
+    }
+    println!(
+        "  CCT {:.0} K, Duv {:.4}, {}",
+        ci.cct_k,
+        ci.duv(),
+        ci.duv_description()
     );
-    make_req(&mut h, b"ST");
+    println!("  CRI Ra {:.1}", ci.cri_ra);
+    println!("  TM-30 Rf {:.0}, Rg {:.0}", cd.tm_30_rf, cd.tm_30_rg);
+}
+
+// $XDG_CACHE_HOME/sekonic-c-7000/captures.json, falling back to ~/.cache
+fn cache_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")));
+    if dir.is_none() {
+        warn!("neither XDG_CACHE_HOME nor HOME is set, not caching");
+    }
+    Some(dir?.join(env!("CARGO_PKG_NAME")).join("captures.json"))
+}
+
+/// `scanned x/total` counter on stderr, redrawn in place. Only shown when
+/// stderr is a terminal, so redirected output stays clean.
+struct Progress {
+    done: u32,
+    total: u32,
+    enabled: bool,
+}
+
+impl Progress {
+    fn new(total: u32) -> Progress {
+        let progress = Progress {
+            done: 0,
+            total,
+            enabled: stderr().is_terminal(),
+        };
+        progress.draw();
+        progress
+    }
+
+    fn inc(&mut self) {
+        self.done += 1;
+        self.draw();
+    }
+
+    /// Erase the counter, so something else can be printed on its line.
+    fn clear(&self) {
+        if self.enabled {
+            eprint!("\r\x1b[K");
+        }
+    }
+
+    fn draw(&self) {
+        if self.enabled {
+            eprint!("\r\x1b[Kscanned {}/{} captures", self.done, self.total);
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// how to write each capture, from the command line
+struct ExportOptions {
+    format: Format,
+    viewing_angle: Option<ViewingAngle>,
+    mkdir: bool,
+    csv: CsvOptions,
+}
+
+// `data` is the ME half if it was already read
+fn export_capture(
+    dev: &mut Device,
+    global_id: u32,
+    ci: &CaptureInfo,
+    data: Option<CaptureData>,
+    local_capture_id: u32,
+    path: &Path,
+    export: &ExportOptions,
+) -> anyhow::Result<()> {
+    output_dir(path, export.mkdir)?;
+    // everything but SPDX needs the ME half too
+    let capture = |dev: &mut Device| -> anyhow::Result<Capture> {
+        let data = match data {
+            Some(data) => data,
+            None => dev.capture_data(global_id)?,
+        };
+        Ok(Capture::new(ci.clone(), data, local_capture_id))
+    };
+    match export.format {
+        Format::Csv => {
+            let capture = capture(dev)?;
+            let mut settings = dev.settings().unwrap_or_default();
+            if let Some(viewing_angle) = export.viewing_angle {
+                settings.viewing_angle = viewing_angle;
+            }
+            write_csv(&capture, &settings, &export.csv, path)?
+        }
+        Format::Json => match &export.csv.fields {
+            Some(fields) => write_selected_json(&capture(dev)?, fields, path)?,
+            None => write_json(&capture(dev)?, path)?,
+        },
+        Format::Spdx => write_spdx(ci, dev.model_info().ok().as_ref(), path)?,
+        Format::Kv => write_kv(&capture(dev)?, path)?,
+        #[cfg(feature = "xlsx")]
+        Format::Xlsx => sekonic_c_7000::write_xlsx(&capture(dev)?, path)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture_info(title: &str) -> CaptureInfo {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/session");
+        let mut ci = CaptureInfo::parse(&fs::read(dir.join("MR0001.resp")).unwrap()).unwrap();
+        ci.title = title.to_owned();
+        ci
+    }
+
+    #[test]
+    fn planned_paths_across_polls() {
+        let dir = Path::new("out");
+        let first = BTreeMap::from([(1, (capture_info("A"), 1))]);
+        let paths = planned_paths(&first, dir, Format::Csv, false, &BTreeMap::new()).unwrap();
+        let written: BTreeMap<_, _> = paths.into_iter().map(|(id, path)| (path, id)).collect();
+
+        // the same title and local index again in a later poll
+        let second = BTreeMap::from([(2, (capture_info("A"), 1)), (3, (capture_info("B"), 1))]);
+        let paths = planned_paths(&second, dir, Format::Csv, false, &written).unwrap();
+        let (earlier, _) = written.first_key_value().unwrap();
+        assert_ne!(&paths[&2], earlier);
+        assert!(
+            paths[&2].to_string_lossy().ends_with("K_2.csv"),
+            "{:?}",
+            paths[&2]
+        );
+        assert!(!written.contains_key(&paths[&3]));
+
+        let e = planned_paths(&second, dir, Format::Csv, true, &written).unwrap_err();
+        assert!(e.to_string().contains("1 (already written), 2"), "{e}");
+    }
 }
@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    str,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{Result, Transport};
+
+/// Answers requests from a table of canned responses, so everything above the
+/// USB layer can run without a meter attached.
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: HashMap<Vec<u8>, Vec<u8>>,
+    releases: Arc<AtomicUsize>,
+}
+
+impl MockTransport {
+    pub fn new() -> MockTransport {
+        MockTransport::default()
+    }
+
+    /// Answer `req` with the payload `resp` (everything after the RESP_OK).
+    pub fn insert(&mut self, req: impl Into<Vec<u8>>, resp: impl Into<Vec<u8>>) {
+        self.responses.insert(req.into(), resp.into());
+    }
+
+    /// Load every `<name>.resp` in `dir`. The request it answers is the
+    /// contents of `<name>.req` if there is one, otherwise `<name>` itself, so
+    /// `MR0001.resp` answers `MR0001`.
+    pub fn from_dir(dir: &Path) -> Result<MockTransport> {
+        let mut ret = MockTransport::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "resp") {
+                continue;
+            }
+            let req_path = path.with_extension("req");
+            let req = if req_path.exists() {
+                fs::read(&req_path)?
+            } else {
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("{} isn't a valid request name", path.display()),
+                        )
+                    })?
+                    .as_bytes()
+                    .to_vec()
+            };
+            ret.insert(req, fs::read(&path)?);
+        }
+        Ok(ret)
+    }
+
+    /// How many times [`Transport::release`] was called, which stays
+    /// readable after the mock is dropped. For checking that whatever owns
+    /// it gives the meter back.
+    pub fn releases(&self) -> Arc<AtomicUsize> {
+        self.releases.clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn request(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        // as an io error, the same as a recorded file that isn't there
+        self.responses.get(req).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "no canned response for {:?}",
+                    str::from_utf8(req).unwrap_or("<binary>")
+                ),
+            )
+            .into()
+        })
+    }
+
+    fn release(&mut self) -> Result<()> {
+        self.releases.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Passes requests through to another transport, saving each request and its
+/// response as `<name>.req` / `<name>.resp` in a directory that
+/// [`MockTransport::from_dir`] can replay.
+pub struct RecordingTransport<T> {
+    inner: T,
+    dir: PathBuf,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn new(inner: T, dir: impl Into<PathBuf>) -> Result<RecordingTransport<T>> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(RecordingTransport { inner, dir })
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn request(&mut self, req: &[u8]) -> Result<Vec<u8>> {
+        let resp = self.inner.request(req)?;
+
+        // requests are short ascii commands like MR0001, but keep the name
+        // filesystem safe in case they aren't. the .req file has the exact bytes
+        let name: String = req
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_alphanumeric() {
+                    char::from(b)
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        fs::write(self.dir.join(format!("{name}.req")), req)?;
+        fs::write(self.dir.join(format!("{name}.resp")), &resp)?;
+        Ok(resp)
+    }
+
+    fn serial(&self) -> Option<String> {
+        self.inner.serial()
+    }
+
+    fn release(&mut self) -> Result<()> {
+        self.inner.release()
+    }
+}
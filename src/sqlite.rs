@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::{to_hex, CaptureInfo};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS captures (
+    device TEXT NOT NULL,
+    global_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    -- when the capture was first written to the database, the meter's own
+    -- timestamp isn't decoded
+    timestamp TEXT NOT NULL,
+    cct_k REAL,
+    duv REAL,
+    illuminance_lx REAL,
+    illuminance_fc REAL,
+    cie1931_x REAL,
+    cie1931_y REAL,
+    cie1976_up REAL,
+    cie1976_vp REAL,
+    dominant_wavelength_nm REAL,
+    purity_pct REAL,
+    ppfd REAL,
+    cri_ra REAL,
+    PRIMARY KEY (device, global_id)
+);
+CREATE TABLE IF NOT EXISTS spectrum (
+    device TEXT NOT NULL,
+    global_id INTEGER NOT NULL,
+    nm INTEGER NOT NULL,
+    value REAL NOT NULL,
+    PRIMARY KEY (device, global_id, nm),
+    FOREIGN KEY (device, global_id) REFERENCES captures (device, global_id)
+);
+";
+
+/// Add `captures` (by global id) to the SQLite database at `path`, creating it
+/// and its tables if needed. Spectra go in their own table at 1nm.
+///
+/// Captures are keyed on `device`, the raw MN response from
+/// [`crate::SekonicDevice::device_key`], and the global id, so writing the
+/// same capture again updates it instead of adding a duplicate. They're all
+/// written in one transaction, so a failure part way leaves the database as
+/// it was.
+pub fn write_sqlite(
+    path: &Path,
+    device: &[u8],
+    captures: &[(u32, &CaptureInfo)],
+) -> anyhow::Result<()> {
+    let mut conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+    let device = to_hex(device);
+    let now = chrono::offset::Local::now().to_rfc3339();
+
+    let tx = conn.transaction()?;
+    {
+        let mut capture = tx.prepare(
+            "INSERT INTO captures VALUES
+                 (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+             ON CONFLICT (device, global_id) DO UPDATE SET
+                 title = excluded.title,
+                 cct_k = excluded.cct_k,
+                 duv = excluded.duv,
+                 illuminance_lx = excluded.illuminance_lx,
+                 illuminance_fc = excluded.illuminance_fc,
+                 cie1931_x = excluded.cie1931_x,
+                 cie1931_y = excluded.cie1931_y,
+                 cie1976_up = excluded.cie1976_up,
+                 cie1976_vp = excluded.cie1976_vp,
+                 dominant_wavelength_nm = excluded.dominant_wavelength_nm,
+                 purity_pct = excluded.purity_pct,
+                 ppfd = excluded.ppfd,
+                 cri_ra = excluded.cri_ra",
+        )?;
+        let mut spectrum = tx.prepare(
+            "INSERT OR REPLACE INTO spectrum (device, global_id, nm, value) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for (global_id, ci) in captures {
+            capture.execute(params![
+                device,
+                global_id,
+                ci.title,
+                now,
+                ci.cct_k,
+                ci.uv_angle,
+                ci.illuminance_lux(),
+                ci.illuminance_fc(),
+                ci.cie1931_x,
+                ci.cie1931_y,
+                ci.cie1976_up,
+                ci.cie1976_vp,
+                ci.dominant_wavelength,
+                ci.purity,
+                ci.ppfd,
+                ci.cri_ra,
+            ])?;
+            for (nm, value) in ci.spectrum_1nm().iter_nm() {
+                spectrum.execute(params![device, global_id, nm, value])?;
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
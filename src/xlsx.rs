@@ -0,0 +1,120 @@
+use std::{io::Write, path::Path};
+
+use rust_xlsxwriter::{Chart, ChartType, Format, Workbook, Worksheet};
+
+use crate::{write_atomically, Capture, Spectrum};
+
+/// The same data as [`crate::write_csv`] as an Excel workbook: the scalar
+/// metrics on a "Summary" sheet, both spectra with a chart of the 1nm one on
+/// "Spectrum", and the color vector bins on "TM-30". Labels match the CSV.
+pub fn write_xlsx(capture: &Capture, path: &Path) -> anyhow::Result<()> {
+    let (ci, cd) = (&capture.info, &capture.data);
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+
+    let summary = workbook.add_worksheet().set_name("Summary")?;
+    summary.write_string_with_format(0, 0, "Title", &bold)?;
+    summary.write_string(0, 1, &capture.title)?;
+    let mut metrics = vec![
+        ("CCT [K]".to_owned(), f64::from(ci.cct_k)),
+        ("⊿uv".into(), ci.uv_angle.into()),
+        ("Illuminance [lx]".into(), ci.illuminance_lux().into()),
+        ("Illuminance [fc]".into(), ci.illuminance_fc().into()),
+    ];
+    if let Some(peak) = ci.spectrum_1nm().peak_wavelength() {
+        metrics.push(("Peak Wavelength [nm]".into(), peak.into()));
+    }
+    metrics.extend([
+        ("Tristimulus Value X".into(), ci.tristimulus_x),
+        ("Tristimulus Value Y".into(), ci.tristimulus_y),
+        ("Tristimulus Value Z".into(), ci.tristimulus_z),
+        ("CIE1931 x".into(), ci.cie1931_x.into()),
+        ("CIE1931 y".into(), ci.cie1931_y.into()),
+        ("CIE1931 z".into(), ci.cie1931_z().into()),
+        ("CIE1976 u'".into(), ci.cie1976_up.into()),
+        ("CIE1976 v'".into(), ci.cie1976_vp.into()),
+        ("CIE1960 u".into(), ci.cie1960_uv().0.into()),
+        ("CIE1960 v".into(), ci.cie1960_uv().1.into()),
+        (
+            "Dominant Wavelength [nm]".into(),
+            ci.dominant_wavelength.into(),
+        ),
+        ("Purity [%]".into(), ci.purity.into()),
+        ("PPFD [umolm⁻²s⁻¹]".into(), ci.ppfd.into()),
+        ("CRI Ra".into(), ci.cri_ra.into()),
+    ]);
+    for (i, val) in ci.cri.iter().enumerate() {
+        metrics.push((format!("CRI R{}", i + 1), (*val).into()));
+    }
+    metrics.extend([
+        ("TM-30 Rf".into(), cd.tm_30_rf.into()),
+        ("TM-30 Rg".into(), cd.tm_30_rg.into()),
+        ("SSIt".into(), cd.ssit.into()),
+        ("SSId".into(), cd.ssid.into()),
+        ("TLCI".into(), cd.tlci.into()),
+    ]);
+    for (row, (label, value)) in (1..).zip(&metrics) {
+        summary.write_string_with_format(row, 0, label, &bold)?;
+        summary.write_number(row, 1, *value)?;
+    }
+    summary.autofit();
+
+    let spectrum = workbook.add_worksheet().set_name("Spectrum")?;
+    let rows = write_spectrum(spectrum, 0, "1nm", &ci.spectrum_1nm(), &bold)?;
+    write_spectrum(spectrum, 3, "5nm", &ci.spectrum_5nm(), &bold)?;
+    let mut chart = Chart::new(ChartType::ScatterStraight);
+    chart
+        .add_series()
+        .set_categories(("Spectrum", 1, 0, rows, 0))
+        .set_values(("Spectrum", 1, 1, rows, 1))
+        .set_name(("Spectrum", 0, 1));
+    chart
+        .x_axis()
+        .set_name("Wavelength [nm]")
+        .set_min(380)
+        .set_max(780);
+    chart.legend().set_hidden();
+    spectrum.insert_chart(1, 6, &chart)?;
+
+    let tm30 = workbook.add_worksheet().set_name("TM-30")?;
+    for (col, header) in (0..).zip([
+        "TM-30 Color Vector Graphic",
+        "Reference Illuminant x",
+        "Reference Illuminant y",
+        "Measured Illuminant x",
+        "Measured Illuminant y",
+    ]) {
+        tm30.write_string_with_format(0, col, header, &bold)?;
+    }
+    for (row, bin) in (1..).zip(&cd.illuminants) {
+        tm30.write_string(row, 0, format!("bin{row}"))?;
+        for (col, val) in (1..).zip(bin) {
+            tm30.write_number(row, col, *val)?;
+        }
+    }
+    tm30.autofit();
+
+    // rendered in memory first so it's written like the other exports
+    let xlsx = workbook.save_to_buffer()?;
+    write_atomically(path, |f| f.write_all(&xlsx))?;
+    Ok(())
+}
+
+// Wavelength and value columns starting at `col`, returning the last row
+fn write_spectrum(
+    sheet: &mut Worksheet,
+    col: u16,
+    step: &str,
+    spectrum: &Spectrum,
+    bold: &Format,
+) -> anyhow::Result<u32> {
+    sheet.write_string_with_format(0, col, "Wavelength [nm]", bold)?;
+    sheet.write_string_with_format(0, col + 1, format!("Spectral Data {step}"), bold)?;
+    let mut last = 0;
+    for (row, (nm, val)) in (1..).zip(spectrum.iter_nm()) {
+        sheet.write_number(row, col, nm)?;
+        sheet.write_number(row, col + 1, val)?;
+        last = row;
+    }
+    Ok(last)
+}
@@ -0,0 +1,55 @@
+//! What the integration tests share: the hand-written session in
+//! `fixtures/session` and comparing output with the golden files next to it.
+//!
+//! The responses in the session are synthetic, made up to look like a
+//! C-7000's, not captured off a real meter. The golden files pin down how
+//! they're parsed and formatted, not that the parsers fit real protocol
+//! bytes.
+//!
+//! When an output changes on purpose, regenerate its golden file with
+//! `UPDATE_FIXTURES=1 cargo test` and commit it with the change.
+
+// each test file is its own crate and only uses some of these
+#![allow(dead_code)]
+
+use std::{env, fs, path::PathBuf};
+
+use sekonic_c_7000::{Capture, MockTransport, SekonicDevice};
+
+pub fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// A device answering from `fixtures/session`, a made up meter with one
+/// title holding one capture.
+pub fn session() -> SekonicDevice<MockTransport> {
+    SekonicDevice::new(MockTransport::from_dir(&fixtures().join("session")).unwrap())
+}
+
+/// The one capture in the session.
+pub fn capture() -> Capture {
+    session().capture(1, 1).unwrap()
+}
+
+/// Where a test can write `name`. Each test needs its own name, they run in
+/// parallel.
+pub fn out(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+/// Check `written` against `fixtures/<name>` byte for byte.
+pub fn assert_golden(name: &str, written: &[u8]) {
+    let golden = fixtures().join(name);
+    if env::var_os("UPDATE_FIXTURES").is_some() {
+        fs::write(&golden, written).unwrap();
+        return;
+    }
+    let expected = fs::read(&golden).unwrap();
+    // as text first so a failure shows the lines that differ
+    assert_eq!(
+        String::from_utf8_lossy(written),
+        String::from_utf8_lossy(&expected),
+        "{name}"
+    );
+    assert_eq!(written, expected, "{name}");
+}
@@ -0,0 +1,63 @@
+//! How long a `SekonicDevice` holds on to the meter, with a `MockTransport`
+//! counting the releases.
+
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use sekonic_c_7000::{MockTransport, SekonicDevice};
+
+fn device() -> (SekonicDevice<MockTransport>, Arc<AtomicUsize>) {
+    let mut dev = common::session();
+    let releases = dev.transport().releases();
+    (dev, releases)
+}
+
+#[test]
+fn drop_releases() {
+    let (mut dev, releases) = device();
+    dev.storage_info().unwrap();
+    assert_eq!(releases.load(Ordering::Relaxed), 0);
+    drop(dev);
+    assert_eq!(releases.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn requests_reuse_the_claim() {
+    let (mut dev, releases) = device();
+    let first = dev.storage_info().unwrap();
+    let second = dev.storage_info().unwrap();
+    assert_eq!(first.num_titles, second.num_titles);
+    assert_eq!(first.num_captures, second.num_captures);
+    assert_eq!(releases.load(Ordering::Relaxed), 0);
+    dev.close().unwrap();
+    assert_eq!(releases.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn drop_after_an_error_releases() {
+    let (mut dev, releases) = device();
+    // not in the session
+    dev.capture_info(2).unwrap_err();
+    drop(dev);
+    assert_eq!(releases.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn close_releases_once() {
+    let (dev, releases) = device();
+    dev.close().unwrap();
+    assert_eq!(releases.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn into_transport_keeps_the_claim() {
+    let (dev, releases) = device();
+    let transport = dev.into_transport();
+    assert_eq!(releases.load(Ordering::Relaxed), 0);
+    drop(transport);
+    assert_eq!(releases.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,30 @@
+//! A whole export against the synthetic session, from the MI request to the
+//! CSV on disk, compared with `fixtures/session.csv`.
+
+mod common;
+
+use std::{fs, path::Path};
+
+use chrono::NaiveDate;
+use sekonic_c_7000::{write_csv, CsvOptions, Settings};
+
+#[test]
+fn csv_matches_fixture() {
+    let mut dev = common::session();
+
+    dev.verify_counts().unwrap();
+    let titles = dev.titles().unwrap();
+    assert_eq!(titles.len(), 1);
+    assert_eq!(titles[0].num_captures, 1);
+    let capture = dev.capture(1, 1).unwrap();
+
+    // the date the capture was taken isn't decoded, so without this it'd be
+    // the time the test ran
+    let options = CsvOptions {
+        saved_at: NaiveDate::from_ymd_opt(2024, 1, 2).and_then(|d| d.and_hms_opt(3, 4, 5)),
+        ..CsvOptions::default()
+    };
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("session.csv");
+    write_csv(&capture, &Settings::default(), &options, &out).unwrap();
+    common::assert_golden("session.csv", &fs::read(&out).unwrap());
+}
@@ -0,0 +1,72 @@
+//! Every response of the synthetic session in `fixtures/session`, parsed
+//! through a `MockTransport` the way they would come off the meter. The captures
+//! are compared as JSON with `fixtures/MR0001.json` and `fixtures/ME0001.json`.
+
+mod common;
+
+use sekonic_c_7000::{MockTransport, SekonicDevice, SekonicError};
+
+#[test]
+fn model_info() {
+    let info = common::session().model_info().unwrap();
+    assert_eq!(info.model, "C-7000");
+}
+
+#[test]
+fn firmware_version() {
+    let fv = common::session().firmware_version().unwrap();
+    assert_eq!(fv.version, "1.03");
+    assert_eq!(fv.to_string(), "1.03");
+}
+
+#[test]
+fn storage_info() {
+    let info = common::session().storage_info().unwrap();
+    assert_eq!(info.num_titles, 1);
+    assert_eq!(info.num_captures, 1);
+}
+
+#[test]
+fn title_info() {
+    let title = common::session().title_info(1).unwrap();
+    assert_eq!(title.name, "TITLE1");
+    assert_eq!(title.num_captures, 1);
+}
+
+#[test]
+fn capture_info() {
+    let mut dev = common::session();
+    let global_id = dev.global_capture_id(1, 1).unwrap();
+    let ci = dev.capture_info(global_id).unwrap();
+    assert_eq!(ci.title, "TITLE1");
+    let json = serde_json::to_string_pretty(&ci).unwrap();
+    common::assert_golden("MR0001.json", json.as_bytes());
+}
+
+#[test]
+fn capture_data() {
+    let cd = common::session().capture_data(1).unwrap();
+    let json = serde_json::to_string_pretty(&cd).unwrap();
+    common::assert_golden("ME0001.json", json.as_bytes());
+}
+
+fn answering_mi(resp: &[u8]) -> SekonicDevice<MockTransport> {
+    let mut mock = MockTransport::new();
+    mock.insert("MI", resp);
+    SekonicDevice::new(mock)
+}
+
+#[test]
+fn one_byte_response() {
+    let e = answering_mi(b"M").storage_info().unwrap_err();
+    assert!(matches!(e, SekonicError::Parse { .. }), "{e}");
+}
+
+#[test]
+fn empty_response() {
+    let e = answering_mi(b"").storage_info().unwrap_err();
+    assert!(
+        matches!(e, SekonicError::EmptyResponse(ref req) if req == "MI"),
+        "{e}"
+    );
+}
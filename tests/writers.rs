@@ -0,0 +1,225 @@
+//! The exporters on the capture from `fixtures/session`, edited to hit the
+//! cases the golden CSV doesn't.
+
+mod common;
+
+use std::{fs, path::Path};
+
+use sekonic_c_7000::{
+    write_all_json, write_combined_csv, write_csv, write_json, write_kv, write_selected_json,
+    write_spdx, write_spectrum_svg, Capture, CsvOptions, CsvStyle, DeviceMetadata, FieldSelection,
+    ModelInfo, Settings,
+};
+
+// Just enough of XML to catch unescaped text: every tag closed in order, and
+// no `<` or stray `&` outside of markup.
+fn assert_well_formed(xml: &str) {
+    let mut open = Vec::new();
+    let mut rest = xml.trim_start_matches(|c| c != '<');
+    while let Some(start) = rest.find('<') {
+        let text = &rest[..start];
+        for (i, _) in text.match_indices('&') {
+            let entity = &text[i..text[i..].find(';').map_or(text.len(), |e| i + e + 1)];
+            assert!(
+                ["&amp;", "&lt;", "&gt;", "&quot;", "&apos;"].contains(&entity),
+                "stray & in {text:?}"
+            );
+        }
+        let end = rest[start..].find('>').expect("unclosed tag") + start;
+        let tag = &rest[start + 1..end];
+        assert!(!tag.contains('<'), "< inside the tag {tag:?}");
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop(), Some(name), "closing tag out of order");
+        } else if !tag.starts_with('?') && !tag.ends_with('/') {
+            open.push(tag.split_whitespace().next().unwrap());
+        }
+        rest = &rest[end + 1..];
+    }
+    assert!(open.is_empty(), "never closed: {open:?}");
+    assert!(!rest.contains('<'), "text after the root element");
+}
+
+#[test]
+fn spdx_escapes_the_title() {
+    let mut capture = common::capture();
+    capture.info.title = r#"a<b&"c""#.to_string();
+    let model = ModelInfo::parse(b"MNB@@C-7000 <&>").unwrap();
+    let path = common::out("escaped.spdx");
+    write_spdx(&capture.info, Some(&model), &path).unwrap();
+
+    let xml = fs::read_to_string(&path).unwrap();
+    assert_well_formed(&xml);
+    assert!(xml.contains("<Description>a&lt;b&amp;&quot;c&quot;</Description>"));
+    assert!(xml.contains("<CatalogNumber>C-7000 &lt;&amp;&gt;</CatalogNumber>"));
+}
+
+#[test]
+fn csv_bom_and_ascii_stay_utf8() {
+    let mut capture = common::capture();
+    capture.info.title = "Küche 温かい".to_string();
+    capture.title = capture.info.title.clone();
+    for (bom, ascii) in [(false, false), (true, false), (false, true), (true, true)] {
+        let options = CsvOptions {
+            bom,
+            ascii,
+            ..CsvOptions::default()
+        };
+        let path = common::out(&format!("utf8_{bom}_{ascii}.csv"));
+        write_csv(&capture, &Settings::default(), &options, &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+
+        let text = match bytes.strip_prefix("\u{feff}".as_bytes()) {
+            Some(rest) => {
+                assert!(bom, "BOM without asking for one");
+                std::str::from_utf8(rest).unwrap()
+            }
+            None => {
+                assert!(!bom, "no BOM");
+                std::str::from_utf8(&bytes).unwrap()
+            }
+        };
+        assert!(text.starts_with("Date Saved,"));
+        assert!(text.contains("Title,Küche 温かい_001_"));
+        if ascii {
+            // only the title isn't ASCII
+            let rest = text.replace("Küche 温かい", "");
+            assert!(rest.is_ascii(), "{rest}");
+            assert!(text.contains("delta_uv,"));
+        } else {
+            assert!(text.contains("⊿uv,"));
+            assert!(text.contains("°"));
+        }
+    }
+}
+
+// What a parse at the wrong offsets looks like
+fn nan_capture() -> Capture {
+    let mut capture = common::capture();
+    let ci = &mut capture.info;
+    ci.cct_k = f32::NAN;
+    ci.tristimulus_x = f64::NAN;
+    ci.cri[0] = f32::INFINITY;
+    ci.spectral_data_5nm[3] = f32::NAN;
+    ci.spectral_data_1nm[10] = f32::NEG_INFINITY;
+    capture.data.tm_30_rf = f32::NAN;
+    capture
+}
+
+// No value in `text` spells out a non-finite number
+fn assert_no_non_finite(name: &str, text: &str) {
+    let token = |t: &&str| {
+        let t = t.trim_matches(|c: char| c == '"' || c.is_whitespace());
+        ["nan", "inf", "-inf", "infinity", "-infinity"].contains(&t.to_ascii_lowercase().as_str())
+    };
+    let bad: Vec<_> = text
+        .split([',', '\n', '=', '<', '>', ' ', ':'])
+        .filter(token)
+        .collect();
+    assert!(bad.is_empty(), "{name} has {bad:?}");
+}
+
+#[test]
+fn nan_through_every_writer() {
+    let capture = nan_capture();
+    let read = |path: &Path| fs::read_to_string(path).unwrap();
+
+    let path = common::out("nan.csv");
+    write_csv(
+        &capture,
+        &Settings::default(),
+        &CsvOptions::default(),
+        &path,
+    )
+    .unwrap();
+    assert_no_non_finite("csv", &read(&path));
+    let strict = CsvOptions {
+        strict: true,
+        ..CsvOptions::default()
+    };
+    write_csv(&capture, &Settings::default(), &strict, &path).unwrap_err();
+
+    let normalized = CsvOptions {
+        style: CsvStyle::Normalized,
+        ..CsvOptions::default()
+    };
+    write_csv(&capture, &Settings::default(), &normalized, &path).unwrap();
+    assert_no_non_finite("normalized csv", &read(&path));
+
+    let path = common::out("nan_combined.csv");
+    let rows = [(1, capture.clone())];
+    write_combined_csv(&rows, true, &CsvOptions::default(), &path).unwrap();
+    assert_no_non_finite("combined csv", &read(&path));
+    write_combined_csv(&rows, true, &strict, &path).unwrap_err();
+
+    // NaN isn't JSON, anything that parses has it as null
+    let path = common::out("nan.json");
+    write_json(&capture, &path).unwrap();
+    serde_json::from_str::<serde_json::Value>(&read(&path)).unwrap();
+    let path = common::out("nan_all.json");
+    write_all_json(
+        std::slice::from_ref(&capture),
+        &DeviceMetadata::default(),
+        &path,
+    )
+    .unwrap();
+    serde_json::from_str::<serde_json::Value>(&read(&path)).unwrap();
+    let path = common::out("nan_selected.json");
+    let fields = FieldSelection::parse("cct_k,spectrum_5nm").unwrap();
+    write_selected_json(&capture, &fields, &path).unwrap();
+    serde_json::from_str::<serde_json::Value>(&read(&path)).unwrap();
+
+    let path = common::out("nan.kv");
+    write_kv(&capture, &path).unwrap();
+    assert_no_non_finite("kv", &read(&path));
+
+    let path = common::out("nan.spdx");
+    write_spdx(&capture.info, None, &path).unwrap();
+    assert_no_non_finite("spdx", &read(&path));
+
+    let path = common::out("nan.svg");
+    write_spectrum_svg(&capture.info, &path).unwrap();
+    assert_no_non_finite("svg", &read(&path));
+}
+
+// a directory in the way makes the final rename fail, after everything has
+// been written to the temporary file
+#[test]
+fn failed_writes_leave_nothing_behind() {
+    let capture = common::capture();
+    let dir = common::out("atomic");
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("in_the_way");
+    fs::create_dir_all(&path).unwrap();
+
+    let rows = [(1, capture.clone())];
+    let fields = FieldSelection::parse("cct_k").unwrap();
+    let results = [
+        write_csv(
+            &capture,
+            &Settings::default(),
+            &CsvOptions::default(),
+            &path,
+        ),
+        write_combined_csv(&rows, true, &CsvOptions::default(), &path),
+        write_json(&capture, &path),
+        write_all_json(
+            std::slice::from_ref(&capture),
+            &DeviceMetadata::default(),
+            &path,
+        ),
+        write_selected_json(&capture, &fields, &path),
+        write_kv(&capture, &path),
+        write_spdx(&capture.info, None, &path),
+        write_spectrum_svg(&capture.info, &path),
+    ];
+    for (i, res) in results.into_iter().enumerate() {
+        assert!(res.is_err(), "writer {i} wrote over a directory");
+    }
+    #[cfg(feature = "xlsx")]
+    sekonic_c_7000::write_xlsx(&capture, &path).unwrap_err();
+    let left: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(left, ["in_the_way"]);
+}